@@ -0,0 +1,194 @@
+use crate::export::{get_label, get_links, group_by_category, ExportOptions};
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::Technology;
+use crate::rendering::orientation::Orientation;
+use std::fmt::Write;
+
+/// Exports a tech tree as a [Graphviz](https://graphviz.org) DOT file.
+///
+/// The nodes are boxes named `t<id>` & categories become clusters.
+/// Links from OR-groups are dashed.
+#[derive(Clone, Debug, Default)]
+pub struct DotExporter {
+    options: ExportOptions,
+    show_ranks: bool,
+}
+
+impl DotExporter {
+    pub fn new(options: ExportOptions) -> Self {
+        DotExporter {
+            options,
+            show_ranks: false,
+        }
+    }
+
+    /// Adds the base name & the rank of ranked technologies as tooltip.
+    /// All ranks of a series share a `group`, which keeps them in a straight line.
+    pub fn with_ranks(mut self, show_ranks: bool) -> Self {
+        self.show_ranks = show_ranks;
+        self
+    }
+
+    pub fn export(&self, tree: &TechnologyTree) -> String {
+        let mut dot = String::from("digraph tech_tree {\n");
+        let rankdir = match self.options.orientation() {
+            Orientation::TopDown => "TB",
+            Orientation::LeftRight => "LR",
+            Orientation::BottomUp => "BT",
+            Orientation::RightLeft => "RL",
+        };
+
+        writeln!(dot, "  rankdir={};", rankdir).unwrap();
+        dot.push_str("  node [shape=box];\n");
+
+        if self.options.show_categories() {
+            let (categories, others) = group_by_category(tree);
+
+            for (index, (category, technologies)) in categories.iter().enumerate() {
+                writeln!(dot, "  subgraph cluster_{} {{", index).unwrap();
+                writeln!(dot, "    label={};", quote(category)).unwrap();
+
+                for technology in technologies {
+                    self.write_node(&mut dot, technology, "    ");
+                }
+
+                dot.push_str("  }\n");
+            }
+
+            for technology in others {
+                self.write_node(&mut dot, technology, "  ");
+            }
+        } else {
+            for technology in tree.technologies() {
+                self.write_node(&mut dot, technology, "  ");
+            }
+        }
+
+        for link in get_links(tree) {
+            let style = if link.is_alternative {
+                " [style=dashed]"
+            } else {
+                ""
+            };
+
+            writeln!(
+                dot,
+                "  t{} -> t{}{};",
+                link.predecessor.id(),
+                link.technology.id(),
+                style
+            )
+            .unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_node(&self, dot: &mut String, technology: &Technology, indent: &str) {
+        let mut attributes = vec![format!(
+            "label={}",
+            quote(&get_label(technology, self.options.show_cost()))
+        )];
+
+        if self.show_ranks {
+            if let Some(rank) = technology.name().get_rank() {
+                let base = technology.name().get_base();
+                let tooltip = format!("Rank {} of {}", rank, base);
+                attributes.push(format!("group={}", quote(base)));
+                attributes.push(format!("tooltip={}", quote(&tooltip)));
+            }
+        }
+
+        writeln!(
+            dot,
+            "{}t{} [{}];",
+            indent,
+            technology.id().id(),
+            attributes.join(", ")
+        )
+        .unwrap();
+    }
+}
+
+/// Creates a quoted DOT string with escaped special characters.
+fn quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::prerequisite::Prerequisite;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    fn create_example_tree() -> TechnologyTree {
+        create_tree(vec![
+            Input::test("Armor 1", vec![]).with_category(Some("Military".to_string())),
+            Input::test("Armor 2", vec!["Armor 1"]).with_category(Some("Military".to_string())),
+            Input::test("Mining", vec![]),
+            Input::with_prerequisite("Forge", Prerequisite::any(vec!["Armor 2", "Mining"])),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_export() {
+        let options = ExportOptions::default().with_orientation(Orientation::LeftRight);
+        let exporter = DotExporter::new(options);
+
+        assert_eq!(
+            exporter.export(&create_example_tree()),
+            "digraph tech_tree {
+  rankdir=LR;
+  node [shape=box];
+  t0 [label=\"Armor 1\"];
+  t1 [label=\"Armor 2\"];
+  t2 [label=\"Mining\"];
+  t3 [label=\"Forge\"];
+  t0 -> t1;
+  t1 -> t3 [style=dashed];
+  t2 -> t3 [style=dashed];
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_export_with_ranks_and_categories() {
+        let options = ExportOptions::default()
+            .with_cost(true)
+            .with_categories(true);
+        let exporter = DotExporter::new(options).with_ranks(true);
+
+        assert_eq!(
+            exporter.export(&create_example_tree()),
+            "digraph tech_tree {
+  rankdir=TB;
+  node [shape=box];
+  subgraph cluster_0 {
+    label=\"Military\";
+    t0 [label=\"Armor 1 (0)\", group=\"Armor\", tooltip=\"Rank 1 of Armor\"];
+    t1 [label=\"Armor 2 (0)\", group=\"Armor\", tooltip=\"Rank 2 of Armor\"];
+  }
+  t2 [label=\"Mining (0)\"];
+  t3 [label=\"Forge (0)\"];
+  t0 -> t1;
+  t1 -> t3 [style=dashed];
+  t2 -> t3 [style=dashed];
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("a \"b\" \\ c"), "\"a \\\"b\\\" \\\\ c\"");
+    }
+}
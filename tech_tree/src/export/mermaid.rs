@@ -0,0 +1,158 @@
+use crate::export::{get_label, get_links, group_by_category, ExportOptions};
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::Technology;
+use crate::rendering::orientation::Orientation;
+use std::fmt::Write;
+
+/// Exports a tech tree as a [Mermaid](https://mermaid.js.org) flowchart.
+///
+/// The nodes use quoted labels to allow any name & categories become subgraphs.
+/// Links from OR-groups are dotted.
+#[derive(Clone, Debug, Default)]
+pub struct MermaidExporter {
+    options: ExportOptions,
+    /// Wraps the flowchart into a Markdown code block.
+    has_fence: bool,
+}
+
+impl MermaidExporter {
+    pub fn new(options: ExportOptions) -> Self {
+        MermaidExporter {
+            options,
+            has_fence: false,
+        }
+    }
+
+    /// Wraps the flowchart into a Markdown code block, which can be embedded into a document.
+    pub fn with_fence(mut self, has_fence: bool) -> Self {
+        self.has_fence = has_fence;
+        self
+    }
+
+    pub fn export(&self, tree: &TechnologyTree) -> String {
+        let mut mermaid = String::new();
+        let direction = match self.options.orientation() {
+            Orientation::TopDown => "TD",
+            Orientation::LeftRight => "LR",
+            Orientation::BottomUp => "BT",
+            Orientation::RightLeft => "RL",
+        };
+
+        if self.has_fence {
+            mermaid.push_str("```mermaid\n");
+        }
+
+        writeln!(mermaid, "flowchart {}", direction).unwrap();
+
+        if self.options.show_categories() {
+            let (categories, others) = group_by_category(tree);
+
+            for (index, (category, technologies)) in categories.iter().enumerate() {
+                writeln!(mermaid, "  subgraph c{} [{}]", index, quote(category)).unwrap();
+
+                for technology in technologies {
+                    self.write_node(&mut mermaid, technology, "    ");
+                }
+
+                mermaid.push_str("  end\n");
+            }
+
+            for technology in others {
+                self.write_node(&mut mermaid, technology, "  ");
+            }
+        } else {
+            for technology in tree.technologies() {
+                self.write_node(&mut mermaid, technology, "  ");
+            }
+        }
+
+        for link in get_links(tree) {
+            let arrow = if link.is_alternative { "-.->" } else { "-->" };
+
+            writeln!(
+                mermaid,
+                "  t{} {} t{}",
+                link.predecessor.id(),
+                arrow,
+                link.technology.id()
+            )
+            .unwrap();
+        }
+
+        if self.has_fence {
+            mermaid.push_str("```\n");
+        }
+
+        mermaid
+    }
+
+    fn write_node(&self, mermaid: &mut String, technology: &Technology, indent: &str) {
+        writeln!(
+            mermaid,
+            "{}t{}[{}]",
+            indent,
+            technology.id().id(),
+            quote(&get_label(technology, self.options.show_cost()))
+        )
+        .unwrap();
+    }
+}
+
+/// Creates a quoted string, which replaces special characters with Mermaid's entity codes.
+fn quote(text: &str) -> String {
+    let escaped = text
+        .replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;");
+
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::prerequisite::Prerequisite;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    #[test]
+    fn test_export() {
+        let tree = create_tree(vec![
+            Input::test("Armor 1", vec![]).with_category(Some("Military".to_string())),
+            Input::test("Mining", vec![]),
+            Input::with_prerequisite("Forge", Prerequisite::any(vec!["Armor 1", "Mining"])),
+            Input::test("C#", vec!["Forge"]),
+        ])
+        .unwrap();
+        let options = ExportOptions::default()
+            .with_categories(true)
+            .with_orientation(Orientation::LeftRight);
+        let exporter = MermaidExporter::new(options).with_fence(true);
+
+        assert_eq!(
+            exporter.export(&tree),
+            "```mermaid
+flowchart LR
+  subgraph c0 [\"Military\"]
+    t0[\"Armor 1\"]
+  end
+  t1[\"Mining\"]
+  t2[\"Forge\"]
+  t3[\"C#35;\"]
+  t0 -.-> t2
+  t1 -.-> t2
+  t2 --> t3
+```
+"
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(
+            quote("<b>\"C#\"</b>"),
+            "\"#lt;b#gt;#quot;C#35;#quot;#lt;/b#gt;\""
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Exports a tech tree into text formats of other tools, which calculate their own layout.
+
+use crate::model::technology::prerequisite::Prerequisite;
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::{Technology, TechnologyId};
+use crate::rendering::orientation::Orientation;
+use thiserror::Error;
+
+pub mod dot;
+pub mod mermaid;
+pub mod plantuml;
+
+/// The options shared by all exporters.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExportOptions {
+    show_cost: bool,
+    show_categories: bool,
+    orientation: Orientation,
+}
+
+impl ExportOptions {
+    /// Displays the research cost after the name.
+    pub fn with_cost(mut self, show_cost: bool) -> Self {
+        self.show_cost = show_cost;
+        self
+    }
+
+    /// Groups the technologies of each category, e.g. into a cluster, subgraph or package.
+    pub fn with_categories(mut self, show_categories: bool) -> Self {
+        self.show_categories = show_categories;
+        self
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn show_cost(&self) -> bool {
+        self.show_cost
+    }
+
+    pub fn show_categories(&self) -> bool {
+        self.show_categories
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ExportError {
+    #[error("Orientation {0:?} is not supported by {1}")]
+    UnsupportedOrientation(Orientation, &'static str),
+}
+
+/// A link from a predecessor to a technology.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Link {
+    pub predecessor: TechnologyId,
+    pub technology: TechnologyId,
+    /// Is the predecessor an alternative inside an OR-group?
+    pub is_alternative: bool,
+}
+
+/// Returns the links of all technologies in id order.
+/// A predecessor used multiple times by the same technology has only 1 link.
+pub(crate) fn get_links(tree: &TechnologyTree) -> Vec<Link> {
+    let mut links = Vec::new();
+
+    for technology in tree.technologies() {
+        let start = links.len();
+        collect_links(
+            technology,
+            technology.prerequisite(),
+            false,
+            start,
+            &mut links,
+        );
+    }
+
+    links
+}
+
+fn collect_links(
+    technology: &Technology,
+    prerequisite: &Prerequisite<TechnologyId>,
+    is_alternative: bool,
+    start: usize,
+    links: &mut Vec<Link>,
+) {
+    match prerequisite {
+        Prerequisite::Technology(id) => {
+            if !links[start..].iter().any(|link| link.predecessor == *id) {
+                links.push(Link {
+                    predecessor: *id,
+                    technology: *technology.id(),
+                    is_alternative,
+                });
+            }
+        }
+        Prerequisite::All(children) => {
+            for child in children {
+                collect_links(technology, child, is_alternative, start, links);
+            }
+        }
+        Prerequisite::Any(children) => {
+            for child in children {
+                collect_links(technology, child, true, start, links);
+            }
+        }
+    }
+}
+
+/// Groups the technologies by category in the order of their first appearance.
+/// Returns the categories & the technologies without one.
+pub(crate) fn group_by_category(
+    tree: &TechnologyTree,
+) -> (Vec<(&str, Vec<&Technology>)>, Vec<&Technology>) {
+    let mut categories: Vec<(&str, Vec<&Technology>)> = Vec::new();
+    let mut others = Vec::new();
+
+    for technology in tree.technologies() {
+        match technology.category() {
+            Some(category) => match categories.iter_mut().find(|(name, _)| *name == category) {
+                Some((_, technologies)) => technologies.push(technology),
+                None => categories.push((category, vec![technology])),
+            },
+            None => others.push(technology),
+        }
+    }
+
+    (categories, others)
+}
+
+/// Returns the label of a technology with its optional research cost.
+pub(crate) fn get_label(technology: &Technology, show_cost: bool) -> String {
+    let name = technology.name().get_full();
+
+    if show_cost {
+        format!("{} ({})", name, technology.cost())
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    #[test]
+    fn test_get_links() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("b", vec![]),
+            Input::with_prerequisite(
+                "c",
+                Prerequisite::Any(vec![
+                    Prerequisite::Technology("a"),
+                    Prerequisite::all(vec!["a", "b"]),
+                ]),
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            get_links(&tree),
+            vec![
+                Link {
+                    predecessor: TechnologyId::new(0),
+                    technology: TechnologyId::new(2),
+                    is_alternative: true,
+                },
+                Link {
+                    predecessor: TechnologyId::new(1),
+                    technology: TechnologyId::new(2),
+                    is_alternative: true,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::export::{get_label, get_links, group_by_category, ExportError, ExportOptions};
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::Technology;
+use crate::rendering::orientation::Orientation;
+use std::fmt::Write;
+
+/// Exports a tech tree as a [PlantUML](https://plantuml.com) diagram.
+///
+/// The nodes are rectangles with an alias `t<id>` & categories become packages.
+/// Links from OR-groups are dotted.
+#[derive(Clone, Debug, Default)]
+pub struct PlantUmlExporter {
+    options: ExportOptions,
+}
+
+impl PlantUmlExporter {
+    pub fn new(options: ExportOptions) -> Self {
+        PlantUmlExporter { options }
+    }
+
+    /// Fails for bottom-up & right-left, because PlantUML only supports top-down & left-right.
+    pub fn export(&self, tree: &TechnologyTree) -> Result<String, ExportError> {
+        let mut uml = String::from("@startuml\n");
+
+        match self.options.orientation() {
+            Orientation::TopDown => {}
+            Orientation::LeftRight => uml.push_str("left to right direction\n"),
+            orientation => {
+                return Err(ExportError::UnsupportedOrientation(orientation, "PlantUML"))
+            }
+        }
+
+        if self.options.show_categories() {
+            let (categories, others) = group_by_category(tree);
+
+            for (category, technologies) in categories {
+                writeln!(uml, "package {} {{", quote(category)).unwrap();
+
+                for technology in technologies {
+                    self.write_node(&mut uml, technology, "  ");
+                }
+
+                uml.push_str("}\n");
+            }
+
+            for technology in others {
+                self.write_node(&mut uml, technology, "");
+            }
+        } else {
+            for technology in tree.technologies() {
+                self.write_node(&mut uml, technology, "");
+            }
+        }
+
+        for link in get_links(tree) {
+            let arrow = if link.is_alternative { "..>" } else { "-->" };
+
+            writeln!(
+                uml,
+                "t{} {} t{}",
+                link.predecessor.id(),
+                arrow,
+                link.technology.id()
+            )
+            .unwrap();
+        }
+
+        uml.push_str("@enduml\n");
+        Ok(uml)
+    }
+
+    fn write_node(&self, uml: &mut String, technology: &Technology, indent: &str) {
+        writeln!(
+            uml,
+            "{}rectangle {} as t{}",
+            indent,
+            quote(&get_label(technology, self.options.show_cost())),
+            technology.id().id()
+        )
+        .unwrap();
+    }
+}
+
+/// Creates a quoted string, which replaces special characters with PlantUML's unicode syntax.
+/// This prevents quotes from ending the string, `\n` from breaking the line & tags from formatting it.
+fn quote(text: &str) -> String {
+    let escaped: String = text
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' | '<' | '>' | '~' => format!("<U+{:04X}>", c as u32),
+            c => c.to_string(),
+        })
+        .collect();
+
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::prerequisite::Prerequisite;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    #[test]
+    fn test_export() {
+        let tree = create_tree(vec![
+            Input::test("Armor 1", vec![]).with_category(Some("Military".to_string())),
+            Input::with_cost("Mining", vec![], 20),
+            Input::with_prerequisite("Forge", Prerequisite::any(vec!["Armor 1", "Mining"])),
+            Input::test("Steel", vec!["Forge"]),
+        ])
+        .unwrap();
+        let options = ExportOptions::default()
+            .with_cost(true)
+            .with_categories(true)
+            .with_orientation(Orientation::LeftRight);
+        let exporter = PlantUmlExporter::new(options);
+
+        assert_eq!(
+            exporter.export(&tree).unwrap(),
+            "@startuml
+left to right direction
+package \"Military\" {
+  rectangle \"Armor 1 (0)\" as t0
+}
+rectangle \"Mining (20)\" as t1
+rectangle \"Forge (0)\" as t2
+rectangle \"Steel (0)\" as t3
+t0 ..> t2
+t1 ..> t2
+t2 --> t3
+@enduml
+"
+        );
+    }
+
+    #[test]
+    fn test_export_with_unsupported_orientation() {
+        let tree = create_tree(vec![Input::test("Mining", vec![])]).unwrap();
+        let options = ExportOptions::default().with_orientation(Orientation::BottomUp);
+
+        assert_eq!(
+            PlantUmlExporter::new(options).export(&tree),
+            Err(ExportError::UnsupportedOrientation(
+                Orientation::BottomUp,
+                "PlantUML"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(
+            quote("<b>\"A\\nB\"</b>"),
+            "\"<U+003C>b<U+003E><U+0022>A<U+005C>nB<U+0022><U+003C>/b<U+003E>\""
+        );
+    }
+}
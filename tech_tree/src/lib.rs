@@ -1,3 +1,4 @@
+pub mod export;
 pub mod model;
 pub mod rendering;
 pub mod usecase;
@@ -1,13 +1,74 @@
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum AddError {
+    #[error("Technologies `{0}` and `{1}` are pinned to the same position")]
+    ConflictingPins(String, String),
     #[error("Technologies {0:?} form a cycle")]
     Cycle(Vec<String>),
+    #[error("Prerequisite of `{0}` contains an empty group")]
+    EmptyGroup(String),
     #[error("Name `{0}` is invalid")]
     InvalidName(String),
+    #[error("Technology `{0}` is pinned to row {1}, but the tree only has {2} technologies")]
+    InvalidRow(String, u32, usize),
+    #[error("Rank `{0}` is missing")]
+    MissingRank(String),
     #[error("Name `{0}` already exists")]
     NameExists(String),
+    #[error("Predecessor `{1}` of `{0}` belongs to a later era")]
+    PredecessorInLaterEra(String, String),
+    #[error("Predecessor `{1}` of `{0}` isn't in an earlier row due to a pinned row")]
+    PredecessorNotAbove(String, String),
+    #[error("Era `{1}` of `{0}` is unknown")]
+    UnknownEra(String, String),
     #[error("Predecessor `{0}` is unknown")]
     UnknownPredecessor(String),
+    #[error("Technology with id {0} is unknown")]
+    UnknownTechnology(usize),
+}
+
+/// An [`AddError`] together with the index of the input causing it.
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    error: AddError,
+    index: Option<usize>,
+}
+
+impl Diagnostic {
+    pub fn new(error: AddError, index: Option<usize>) -> Self {
+        Diagnostic { error, index }
+    }
+
+    pub fn error(&self) -> &AddError {
+        &self.error
+    }
+
+    /// Returns the index of the input causing the error, if there is one.
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn into_error(self) -> AddError {
+        self.error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ResearchError {
+    #[error("Technology `{0}` is already researched")]
+    AlreadyResearched(String),
+    #[error("Technology `{0}` is locked")]
+    Locked(String),
+    #[error("Technology `{0}` is not tracked by the research state of another tree")]
+    NotInState(String),
+    #[error("Technology with id {0} is unknown")]
+    UnknownTechnology(usize),
 }
@@ -1,6 +1,11 @@
 use crate::model::technology::name::TechnologyName;
+use crate::model::technology::pin::Pin;
+use crate::model::technology::prerequisite::Prerequisite;
 
 pub mod name;
+pub mod pin;
+pub mod prerequisite;
+pub mod research;
 pub mod tree;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -20,31 +25,76 @@ impl TechnologyId {
 pub struct Technology {
     id: TechnologyId,
     name: TechnologyName,
+    prerequisite: Prerequisite<TechnologyId>,
     predecessors: Vec<TechnologyId>,
     successors: Vec<TechnologyId>,
+    cost: u32,
+    pin: Pin,
+    era: Option<String>,
+    category: Option<String>,
+    description: Option<String>,
+    icon: Option<String>,
 }
 
 impl Technology {
     pub fn new(
         id: TechnologyId,
         name: TechnologyName,
-        predecessors: Vec<TechnologyId>,
+        prerequisite: Prerequisite<TechnologyId>,
         successors: Vec<TechnologyId>,
+        cost: u32,
     ) -> Self {
         Technology {
             id,
             name,
-            predecessors,
+            predecessors: prerequisite.get_technologies(),
+            prerequisite,
             successors,
+            cost,
+            pin: Pin::default(),
+            era: None,
+            category: None,
+            description: None,
+            icon: None,
         }
     }
 
+    pub fn with_pin(self, pin: Pin) -> Self {
+        Technology { pin, ..self }
+    }
+
+    pub fn with_era(self, era: Option<String>) -> Self {
+        Technology { era, ..self }
+    }
+
+    pub fn with_category(self, category: Option<String>) -> Self {
+        Technology { category, ..self }
+    }
+
+    pub fn with_description(self, description: Option<String>) -> Self {
+        Technology {
+            description,
+            ..self
+        }
+    }
+
+    pub fn with_icon(self, icon: Option<String>) -> Self {
+        Technology { icon, ..self }
+    }
+
     pub fn simple(id: usize) -> Self {
         Technology {
             id: TechnologyId(id),
             name: TechnologyName::Simple(format!("Tech {}", id)),
+            prerequisite: Prerequisite::default(),
             predecessors: Vec::new(),
             successors: Vec::new(),
+            cost: 0,
+            pin: Pin::default(),
+            era: None,
+            category: None,
+            description: None,
+            icon: None,
         }
     }
 
@@ -54,11 +104,21 @@ impl Technology {
         predecessors: Vec<usize>,
         successors: Vec<usize>,
     ) -> Self {
+        let predecessors: Vec<TechnologyId> =
+            predecessors.into_iter().map(TechnologyId::new).collect();
+
         Technology {
             id: TechnologyId(id),
             name: TechnologyName::Simple(name.to_string()),
-            predecessors: predecessors.into_iter().map(TechnologyId::new).collect(),
+            prerequisite: Prerequisite::all(predecessors.clone()),
+            predecessors,
             successors: successors.into_iter().map(TechnologyId::new).collect(),
+            cost: 0,
+            pin: Pin::default(),
+            era: None,
+            category: None,
+            description: None,
+            icon: None,
         }
     }
 
@@ -70,6 +130,12 @@ impl Technology {
         &self.name
     }
 
+    /// Returns the expression defining which predecessors are needed.
+    pub fn prerequisite(&self) -> &Prerequisite<TechnologyId> {
+        &self.prerequisite
+    }
+
+    /// Returns all technologies used by the prerequisite.
     pub fn predecessors(&self) -> &Vec<TechnologyId> {
         &self.predecessors
     }
@@ -87,23 +153,113 @@ impl Technology {
     pub fn successors(&self) -> &Vec<TechnologyId> {
         &self.successors
     }
+
+    /// Returns the research cost of this technology without its predecessors.
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    /// Returns the manual position in the layout.
+    pub fn pin(&self) -> &Pin {
+        &self.pin
+    }
+
+    /// Returns the era, e.g. `Ancient`. See [`TechnologyTree::eras`](tree::TechnologyTree::eras).
+    pub fn era(&self) -> Option<&str> {
+        self.era.as_deref()
+    }
+
+    /// Returns the category, e.g. `Military`.
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    /// Returns the explanation for players, what this technology does.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the path of an image file.
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Input {
     name: String,
-    predecessors: Vec<String>,
+    prerequisite: Prerequisite<String>,
+    cost: u32,
+    pin: Pin,
+    era: Option<String>,
+    category: Option<String>,
+    description: Option<String>,
+    icon: Option<String>,
 }
 
 impl Input {
-    pub fn new(name: String, predecessors: Vec<String>) -> Self {
-        Input { name, predecessors }
+    pub fn new(name: String, prerequisite: Prerequisite<String>, cost: u32) -> Self {
+        Input {
+            name,
+            prerequisite,
+            cost,
+            pin: Pin::default(),
+            era: None,
+            category: None,
+            description: None,
+            icon: None,
+        }
+    }
+
+    pub fn with_pin(self, pin: Pin) -> Self {
+        Input { pin, ..self }
+    }
+
+    pub fn with_era(self, era: Option<String>) -> Self {
+        Input { era, ..self }
+    }
+
+    pub fn with_category(self, category: Option<String>) -> Self {
+        Input { category, ..self }
+    }
+
+    pub fn with_description(self, description: Option<String>) -> Self {
+        Input {
+            description,
+            ..self
+        }
+    }
+
+    pub fn with_icon(self, icon: Option<String>) -> Self {
+        Input { icon, ..self }
     }
 
     pub fn test(name: &str, predecessors: Vec<&str>) -> Self {
         Input {
             name: name.to_string(),
-            predecessors: predecessors.into_iter().map(|p| p.to_string()).collect(),
+            prerequisite: Prerequisite::all(
+                predecessors.into_iter().map(|p| p.to_string()).collect(),
+            ),
+            cost: 0,
+            pin: Pin::default(),
+            era: None,
+            category: None,
+            description: None,
+            icon: None,
+        }
+    }
+
+    pub fn with_prerequisite(name: &str, prerequisite: Prerequisite<&str>) -> Self {
+        Input {
+            prerequisite: prerequisite.map(&|p| p.to_string()),
+            ..Input::test(name, vec![])
+        }
+    }
+
+    pub fn with_cost(name: &str, predecessors: Vec<&str>, cost: u32) -> Self {
+        Input {
+            cost,
+            ..Input::test(name, predecessors)
         }
     }
 
@@ -111,8 +267,36 @@ impl Input {
         &self.name
     }
 
-    pub fn predecessors(&self) -> &Vec<String> {
-        &self.predecessors
+    pub fn prerequisite(&self) -> &Prerequisite<String> {
+        &self.prerequisite
+    }
+
+    pub fn set_prerequisite(&mut self, prerequisite: Prerequisite<String>) {
+        self.prerequisite = prerequisite;
+    }
+
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    pub fn pin(&self) -> &Pin {
+        &self.pin
+    }
+
+    pub fn era(&self) -> Option<&str> {
+        self.era.as_deref()
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
     }
 }
 
@@ -159,6 +343,18 @@ mod tests {
         let input = Input::test("A", vec!["B", "C"]);
 
         assert_eq!(input.name(), "A");
-        assert_eq!(input.predecessors(), &vec!["B", "C"]);
+        assert_eq!(
+            input.prerequisite(),
+            &Prerequisite::all(vec!["B".to_string(), "C".to_string()])
+        );
+        assert_eq!(input.cost(), 0);
+    }
+
+    #[test]
+    fn test_input_with_cost() {
+        let input = Input::with_cost("A", vec![], 42);
+
+        assert_eq!(input.name(), "A");
+        assert_eq!(input.cost(), 42);
     }
 }
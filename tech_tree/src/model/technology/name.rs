@@ -40,6 +40,20 @@ impl TechnologyName {
             TechnologyName::Ranked { full, .. } => full,
         }
     }
+
+    pub fn get_base(&self) -> &str {
+        match self {
+            TechnologyName::Simple(name) => name,
+            TechnologyName::Ranked { base, .. } => base,
+        }
+    }
+
+    pub fn get_rank(&self) -> Option<u8> {
+        match self {
+            TechnologyName::Simple(_) => None,
+            TechnologyName::Ranked { rank, .. } => Some(*rank),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -80,6 +94,17 @@ mod tests {
         assert_eq!(TechnologyName::new("  UVW   ").unwrap().get_full(), "UVW");
     }
 
+    #[test]
+    fn test_get_base_and_rank() {
+        let simple = TechnologyName::new("Simple").unwrap();
+        let ranked = TechnologyName::new("Armor 3").unwrap();
+
+        assert_eq!(simple.get_base(), "Simple");
+        assert_eq!(simple.get_rank(), None);
+        assert_eq!(ranked.get_base(), "Armor");
+        assert_eq!(ranked.get_rank(), Some(3));
+    }
+
     #[test]
     fn test_get_full_ranked() {
         assert_eq!(
@@ -0,0 +1,22 @@
+/// A manual position of a technology in the layout. Unset coordinates are calculated automatically.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Pin {
+    row: Option<u32>,
+    column: Option<u32>,
+}
+
+impl Pin {
+    pub fn new(row: Option<u32>, column: Option<u32>) -> Self {
+        Pin { row, column }
+    }
+
+    /// Returns the row, which replaces the depth of the technology.
+    pub fn row(&self) -> Option<u32> {
+        self.row
+    }
+
+    /// Returns the position inside the row starting at 0.
+    pub fn column(&self) -> Option<u32> {
+        self.column
+    }
+}
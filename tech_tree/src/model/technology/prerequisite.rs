@@ -0,0 +1,311 @@
+/// An expression defining which technologies are needed to research another one.
+///
+/// The generic type is either the name or the id of a technology.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Prerequisite<T> {
+    /// Needs a specific technology.
+    Technology(T),
+    /// Needs all the prerequisites. An empty list needs nothing.
+    All(Vec<Prerequisite<T>>),
+    /// Needs at least one of the prerequisites.
+    Any(Vec<Prerequisite<T>>),
+}
+
+impl<T> Default for Prerequisite<T> {
+    fn default() -> Self {
+        Prerequisite::All(Vec::new())
+    }
+}
+
+impl<T: Clone + PartialEq> Prerequisite<T> {
+    /// Creates the traditional prerequisite, which needs all technologies.
+    pub fn all(technologies: Vec<T>) -> Self {
+        Prerequisite::All(
+            technologies
+                .into_iter()
+                .map(Prerequisite::Technology)
+                .collect(),
+        )
+    }
+
+    /// Creates a prerequisite, which needs one of the technologies.
+    pub fn any(technologies: Vec<T>) -> Self {
+        Prerequisite::Any(
+            technologies
+                .into_iter()
+                .map(Prerequisite::Technology)
+                .collect(),
+        )
+    }
+
+    /// Returns all technologies used by the expression without duplicates.
+    pub fn get_technologies(&self) -> Vec<T> {
+        let mut technologies = Vec::new();
+        self.collect_technologies(&mut technologies);
+        technologies
+    }
+
+    fn collect_technologies(&self, technologies: &mut Vec<T>) {
+        match self {
+            Prerequisite::Technology(technology) => {
+                if !technologies.contains(technology) {
+                    technologies.push(technology.clone());
+                }
+            }
+            Prerequisite::All(children) | Prerequisite::Any(children) => {
+                for child in children {
+                    child.collect_technologies(technologies);
+                }
+            }
+        }
+    }
+
+    /// Is the expression satisfied, if only the technologies accepted by the function are known?
+    pub fn is_satisfied<F: Fn(&T) -> bool>(&self, is_known: &F) -> bool {
+        match self {
+            Prerequisite::Technology(technology) => is_known(technology),
+            Prerequisite::All(children) => children.iter().all(|c| c.is_satisfied(is_known)),
+            Prerequisite::Any(children) => children.iter().any(|c| c.is_satisfied(is_known)),
+        }
+    }
+
+    /// Evaluates the expression with a value per technology.
+    /// [`Prerequisite::All`] needs the maximum and [`Prerequisite::Any`] the minimum of its children.
+    /// Returns [`None`], if the expression isn't satisfied by the technologies with a value.
+    pub fn evaluate<F: Fn(&T) -> Option<u32>>(&self, get_value: &F) -> Option<u32> {
+        match self {
+            Prerequisite::Technology(technology) => get_value(technology),
+            Prerequisite::All(children) => children
+                .iter()
+                .map(|c| c.evaluate(get_value))
+                .try_fold(0, |max, value| value.map(|value| value.max(max))),
+            Prerequisite::Any(children) => {
+                children.iter().filter_map(|c| c.evaluate(get_value)).min()
+            }
+        }
+    }
+
+    /// Does the expression contain a group without children? Only the top level [`Prerequisite::All`] is allowed to be empty.
+    pub fn has_empty_group(&self) -> bool {
+        match self {
+            Prerequisite::All(children) => children.iter().any(|c| c.is_empty_group()),
+            _ => self.is_empty_group(),
+        }
+    }
+
+    fn is_empty_group(&self) -> bool {
+        match self {
+            Prerequisite::Technology(_) => false,
+            Prerequisite::All(children) | Prerequisite::Any(children) => {
+                children.is_empty() || children.iter().any(|c| c.is_empty_group())
+            }
+        }
+    }
+
+    /// Adds a technology, which is needed in addition to the current expression.
+    pub fn and_technology(self, technology: T) -> Self {
+        let new = Prerequisite::Technology(technology);
+
+        match self {
+            Prerequisite::All(mut children) => {
+                if !children.contains(&new) {
+                    children.insert(0, new);
+                }
+                Prerequisite::All(children)
+            }
+            prerequisite => Prerequisite::All(vec![new, prerequisite]),
+        }
+    }
+
+    /// Removes a technology from the expression. Groups, that become empty, are removed too.
+    pub fn remove(&self, technology: &T) -> Option<Self> {
+        self.retain(&|t| t != technology)
+    }
+
+    /// Keeps only the technologies accepted by the function. Groups, that become empty, are removed too.
+    pub fn retain<F: Fn(&T) -> bool>(&self, keep: &F) -> Option<Self> {
+        match self {
+            Prerequisite::Technology(technology) => {
+                if keep(technology) {
+                    Some(self.clone())
+                } else {
+                    None
+                }
+            }
+            Prerequisite::All(children) => {
+                Self::retain_in_group(children, keep).map(Prerequisite::All)
+            }
+            Prerequisite::Any(children) => {
+                Self::retain_in_group(children, keep).map(Prerequisite::Any)
+            }
+        }
+    }
+
+    fn retain_in_group<F: Fn(&T) -> bool>(children: &[Self], keep: &F) -> Option<Vec<Self>> {
+        let children: Vec<Self> = children.iter().filter_map(|c| c.retain(keep)).collect();
+
+        if children.is_empty() {
+            None
+        } else {
+            Some(children)
+        }
+    }
+
+    /// Converts each technology of the expression.
+    pub fn map<U, F: Fn(&T) -> U>(&self, convert: &F) -> Prerequisite<U> {
+        match self {
+            Prerequisite::Technology(technology) => Prerequisite::Technology(convert(technology)),
+            Prerequisite::All(children) => {
+                Prerequisite::All(children.iter().map(|c| c.map(convert)).collect())
+            }
+            Prerequisite::Any(children) => {
+                Prerequisite::Any(children.iter().map(|c| c.map(convert)).collect())
+            }
+        }
+    }
+
+    /// Converts each technology of the expression & stops at the first error.
+    pub fn try_map<U, E, F: Fn(&T) -> Result<U, E>>(
+        &self,
+        convert: &F,
+    ) -> Result<Prerequisite<U>, E> {
+        Ok(match self {
+            Prerequisite::Technology(technology) => Prerequisite::Technology(convert(technology)?),
+            Prerequisite::All(children) => Prerequisite::All(
+                children
+                    .iter()
+                    .map(|c| c.try_map(convert))
+                    .collect::<Result<_, E>>()?,
+            ),
+            Prerequisite::Any(children) => Prerequisite::Any(
+                children
+                    .iter()
+                    .map(|c| c.try_map(convert))
+                    .collect::<Result<_, E>>()?,
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Prerequisite::*;
+
+    #[test]
+    fn test_get_technologies() {
+        let prerequisite = All(vec![
+            Technology(1),
+            Any(vec![Technology(2), All(vec![Technology(3), Technology(1)])]),
+        ]);
+
+        assert_eq!(prerequisite.get_technologies(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_is_satisfied() {
+        let prerequisite = Any(vec![All(vec![Technology(1), Technology(2)]), Technology(3)]);
+
+        assert!(!prerequisite.is_satisfied(&|_| false));
+        assert!(!prerequisite.is_satisfied(&|t| *t == 1));
+        assert!(prerequisite.is_satisfied(&|t| *t != 3));
+        assert!(prerequisite.is_satisfied(&|t| *t == 3));
+        assert!(Prerequisite::<u32>::default().is_satisfied(&|_| false));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let prerequisite = Any(vec![All(vec![Technology(1), Technology(2)]), Technology(3)]);
+
+        assert_eq!(prerequisite.evaluate(&|_| None), None);
+        assert_eq!(prerequisite.evaluate(&|t| Some(*t)), Some(2));
+        assert_eq!(
+            prerequisite.evaluate(&|t| Some(*t).filter(|v| *v != 1)),
+            Some(3)
+        );
+        assert_eq!(Prerequisite::<u32>::default().evaluate(&|_| None), Some(0));
+    }
+
+    #[test]
+    fn test_has_empty_group() {
+        assert!(!Prerequisite::<u32>::default().has_empty_group());
+        assert!(!Prerequisite::all(vec![1, 2]).has_empty_group());
+        assert!(!Prerequisite::any(vec![1, 2]).has_empty_group());
+        assert!(Prerequisite::<u32>::Any(vec![]).has_empty_group());
+        assert!(All(vec![Technology(1), Any(vec![])]).has_empty_group());
+        assert!(Any(vec![Technology(1), All(vec![])]).has_empty_group());
+    }
+
+    #[test]
+    fn test_and_technology() {
+        assert_eq!(
+            Prerequisite::all(vec![1, 2]).and_technology(3),
+            Prerequisite::all(vec![3, 1, 2])
+        );
+        assert_eq!(
+            Prerequisite::all(vec![1, 2]).and_technology(2),
+            Prerequisite::all(vec![1, 2])
+        );
+        assert_eq!(
+            Prerequisite::any(vec![1, 2]).and_technology(3),
+            All(vec![Technology(3), Prerequisite::any(vec![1, 2])])
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let prerequisite = All(vec![Technology(1), Any(vec![Technology(2), Technology(3)])]);
+
+        assert_eq!(
+            prerequisite.remove(&2),
+            Some(All(vec![Technology(1), Any(vec![Technology(3)])]))
+        );
+        assert_eq!(prerequisite.remove(&4), Some(prerequisite.clone()));
+        assert_eq!(
+            Any(vec![Technology(1), All(vec![Technology(2)])]).remove(&2),
+            Some(Any(vec![Technology(1)]))
+        );
+        assert_eq!(Prerequisite::all(vec![1]).remove(&1), None);
+    }
+
+    #[test]
+    fn test_retain() {
+        let prerequisite = All(vec![
+            Technology(1),
+            Any(vec![Technology(2), Technology(3), All(vec![])]),
+        ]);
+
+        assert_eq!(
+            prerequisite.retain(&|t| *t > 1),
+            Some(All(vec![Any(vec![Technology(2), Technology(3)])]))
+        );
+        assert_eq!(prerequisite.retain(&|_| false), None);
+    }
+
+    #[test]
+    fn test_map() {
+        let prerequisite = Any(vec![All(vec![Technology(1), Technology(2)]), Technology(3)]);
+
+        assert_eq!(
+            prerequisite.map(&|t| t * 10),
+            Any(vec![
+                All(vec![Technology(10), Technology(20)]),
+                Technology(30)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_try_map() {
+        let prerequisite = Any(vec![All(vec![Technology(1), Technology(2)]), Technology(3)]);
+
+        assert_eq!(
+            prerequisite.try_map(&|t| if *t < 4 { Ok(t * 10) } else { Err(*t) }),
+            Ok(Any(vec![
+                All(vec![Technology(10), Technology(20)]),
+                Technology(30)
+            ]))
+        );
+        assert_eq!(prerequisite.try_map::<u32, _, _>(&|t| Err(*t)), Err(1));
+    }
+}
@@ -0,0 +1,207 @@
+use crate::model::error::ResearchError;
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::TechnologyId;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ResearchStatus {
+    /// The technology was already researched.
+    Researched,
+    /// The prerequisite is satisfied, so the technology can be researched next.
+    Available,
+    /// The prerequisite is not satisfied yet.
+    Locked,
+}
+
+/// Tracks the progress of a player through a [`TechnologyTree`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResearchState {
+    researched: Vec<bool>,
+}
+
+impl ResearchState {
+    pub fn new(tree: &TechnologyTree) -> Self {
+        ResearchState {
+            researched: vec![false; tree.technologies().len()],
+        }
+    }
+
+    pub fn is_researched(&self, id: TechnologyId) -> bool {
+        self.researched.get(id.id()).copied().unwrap_or_default()
+    }
+
+    pub fn get_status(&self, tree: &TechnologyTree, id: TechnologyId) -> ResearchStatus {
+        if self.is_researched(id) {
+            return ResearchStatus::Researched;
+        }
+
+        let is_prerequisite_satisfied = tree
+            .get(id)
+            .map(|technology| {
+                technology
+                    .prerequisite()
+                    .is_satisfied(&|predecessor| self.is_researched(*predecessor))
+            })
+            .unwrap_or_default();
+
+        if is_prerequisite_satisfied {
+            ResearchStatus::Available
+        } else {
+            ResearchStatus::Locked
+        }
+    }
+
+    pub fn get_researched(&self, tree: &TechnologyTree) -> Vec<TechnologyId> {
+        self.get_with_status(tree, ResearchStatus::Researched)
+    }
+
+    pub fn get_available(&self, tree: &TechnologyTree) -> Vec<TechnologyId> {
+        self.get_with_status(tree, ResearchStatus::Available)
+    }
+
+    pub fn get_locked(&self, tree: &TechnologyTree) -> Vec<TechnologyId> {
+        self.get_with_status(tree, ResearchStatus::Locked)
+    }
+
+    fn get_with_status(&self, tree: &TechnologyTree, status: ResearchStatus) -> Vec<TechnologyId> {
+        tree.technologies()
+            .iter()
+            .map(|technology| *technology.id())
+            .filter(|id| self.get_status(tree, *id) == status)
+            .collect()
+    }
+
+    /// Researches an available technology.
+    pub fn research(
+        &mut self,
+        tree: &TechnologyTree,
+        id: TechnologyId,
+    ) -> Result<(), ResearchError> {
+        let technology = tree
+            .get(id)
+            .ok_or(ResearchError::UnknownTechnology(id.id()))?;
+        let name = technology.name().get_full().to_string();
+
+        if id.id() >= self.researched.len() {
+            return Err(ResearchError::NotInState(name));
+        }
+
+        match self.get_status(tree, id) {
+            ResearchStatus::Researched => Err(ResearchError::AlreadyResearched(name)),
+            ResearchStatus::Locked => Err(ResearchError::Locked(name)),
+            ResearchStatus::Available => {
+                self.researched[id.id()] = true;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::prerequisite::Prerequisite;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    #[test]
+    fn test_new() {
+        let tree = init_tree();
+        let state = ResearchState::new(&tree);
+
+        assert_eq!(state.get_researched(&tree), vec![]);
+        assert_eq!(state.get_available(&tree), ids(vec![0, 1]));
+        assert_eq!(state.get_locked(&tree), ids(vec![2, 3]));
+    }
+
+    #[test]
+    fn test_research() {
+        let tree = init_tree();
+        let mut state = ResearchState::new(&tree);
+
+        assert_eq!(state.research(&tree, TechnologyId::new(0)), Ok(()));
+
+        assert_eq!(state.get_researched(&tree), ids(vec![0]));
+        assert_eq!(state.get_available(&tree), ids(vec![1]));
+        assert_eq!(state.get_locked(&tree), ids(vec![2, 3]));
+
+        assert_eq!(state.research(&tree, TechnologyId::new(1)), Ok(()));
+
+        assert_eq!(state.get_researched(&tree), ids(vec![0, 1]));
+        assert_eq!(state.get_available(&tree), ids(vec![2]));
+        assert_eq!(state.get_locked(&tree), ids(vec![3]));
+    }
+
+    #[test]
+    fn test_research_locked() {
+        let tree = init_tree();
+        let mut state = ResearchState::new(&tree);
+
+        assert_eq!(
+            state.research(&tree, TechnologyId::new(2)),
+            Err(ResearchError::Locked("t2".to_string()))
+        );
+        assert!(!state.is_researched(TechnologyId::new(2)));
+    }
+
+    #[test]
+    fn test_research_twice() {
+        let tree = init_tree();
+        let mut state = ResearchState::new(&tree);
+
+        assert_eq!(state.research(&tree, TechnologyId::new(0)), Ok(()));
+        assert_eq!(
+            state.research(&tree, TechnologyId::new(0)),
+            Err(ResearchError::AlreadyResearched("t0".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_research_unknown() {
+        let tree = init_tree();
+        let mut state = ResearchState::new(&tree);
+
+        assert_eq!(
+            state.research(&tree, TechnologyId::new(4)),
+            Err(ResearchError::UnknownTechnology(4))
+        );
+    }
+
+    #[test]
+    fn test_research_with_other_tree() {
+        let mut state = ResearchState::new(&create_tree(vec![]).unwrap());
+
+        assert_eq!(
+            state.research(&init_tree(), TechnologyId::new(0)),
+            Err(ResearchError::NotInState("t0".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_research_with_any() {
+        let tree = create_tree(vec![
+            Input::test("t0", vec![]),
+            Input::test("t1", vec![]),
+            Input::with_prerequisite("t2", Prerequisite::any(vec!["t0", "t1"])),
+        ])
+        .unwrap();
+        let mut state = ResearchState::new(&tree);
+
+        assert_eq!(state.get_locked(&tree), ids(vec![2]));
+        assert_eq!(state.research(&tree, TechnologyId::new(1)), Ok(()));
+        assert_eq!(state.get_available(&tree), ids(vec![0, 2]));
+    }
+
+    fn ids(ids: Vec<usize>) -> Vec<TechnologyId> {
+        ids.into_iter().map(TechnologyId::new).collect()
+    }
+
+    fn init_tree() -> TechnologyTree {
+        create_tree(vec![
+            Input::test("t0", vec![]),
+            Input::test("t1", vec![]),
+            Input::test("t2", vec!["t0", "t1"]),
+            Input::test("t3", vec!["t2"]),
+        ])
+        .unwrap()
+    }
+}
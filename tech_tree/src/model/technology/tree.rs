@@ -3,11 +3,20 @@ use crate::model::technology::{Technology, TechnologyId};
 #[derive(Clone, Debug, PartialEq)]
 pub struct TechnologyTree {
     technologies: Vec<Technology>,
+    eras: Vec<String>,
 }
 
 impl TechnologyTree {
     pub fn new(technologies: Vec<Technology>) -> Self {
-        TechnologyTree { technologies }
+        TechnologyTree {
+            technologies,
+            eras: Vec::new(),
+        }
+    }
+
+    /// The eras in chronological order.
+    pub fn with_eras(self, eras: Vec<String>) -> Self {
+        TechnologyTree { eras, ..self }
     }
 
     pub fn technologies(&self) -> &Vec<Technology> {
@@ -17,6 +26,24 @@ impl TechnologyTree {
     pub fn get(&self, id: TechnologyId) -> Option<&Technology> {
         self.technologies.get(id.0)
     }
+
+    pub fn find(&self, name: &str) -> Option<&Technology> {
+        self.technologies
+            .iter()
+            .find(|technology| technology.name().get_full() == name)
+    }
+
+    /// Returns the eras in chronological order.
+    pub fn eras(&self) -> &Vec<String> {
+        &self.eras
+    }
+
+    /// Returns the index of the era of a technology, if it has a known era.
+    pub fn get_era_index(&self, technology: &Technology) -> Option<usize> {
+        technology
+            .era()
+            .and_then(|era| self.eras.iter().position(|other| other == era))
+    }
 }
 
 #[cfg(test)]
@@ -36,4 +63,30 @@ mod tests {
         assert_eq!(tree.get(TechnologyId::new(2)), Some(&Technology::simple(2)));
         assert_eq!(tree.get(TechnologyId::new(3)), None);
     }
+
+    #[test]
+    fn test_find() {
+        let tree = TechnologyTree::new(vec![Technology::simple(0), Technology::simple(1)]);
+
+        assert_eq!(tree.find("Tech 0"), Some(&Technology::simple(0)));
+        assert_eq!(tree.find("Tech 1"), Some(&Technology::simple(1)));
+        assert_eq!(tree.find("Tech 2"), None);
+    }
+
+    #[test]
+    fn test_get_era_index() {
+        let tree = TechnologyTree::new(vec![
+            Technology::simple(0).with_era(Some("Medieval".to_string())),
+            Technology::simple(1),
+            Technology::simple(2).with_era(Some("Ancient".to_string())),
+            Technology::simple(3).with_era(Some("Modern".to_string())),
+        ])
+        .with_eras(vec!["Ancient".to_string(), "Medieval".to_string()]);
+        let get_era_index = |id: usize| tree.get_era_index(&tree.technologies()[id]);
+
+        assert_eq!(get_era_index(0), Some(1));
+        assert_eq!(get_era_index(1), None);
+        assert_eq!(get_era_index(2), Some(0));
+        assert_eq!(get_era_index(3), None);
+    }
 }
@@ -0,0 +1,479 @@
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::TechnologyId;
+use crate::rendering::grid::{Grid, GridCell};
+use crate::rendering::layout::{apply_column_pins, calculate_row_sizes, calculate_rows, Layout};
+use itertools::izip;
+
+const ORDERING_ITERATIONS: usize = 12;
+const PLACEMENT_ITERATIONS: usize = 8;
+
+/// How the position of a technology inside its row is calculated from its neighbours.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CrossingHeuristic {
+    /// The average position of the neighbours.
+    Barycenter,
+    /// The median position of the neighbours.
+    Median,
+}
+
+impl CrossingHeuristic {
+    fn calculate(&self, mut positions: Vec<f64>) -> Option<f64> {
+        if positions.is_empty() {
+            return None;
+        }
+
+        Some(match self {
+            CrossingHeuristic::Barycenter => positions.iter().sum::<f64>() / positions.len() as f64,
+            CrossingHeuristic::Median => {
+                positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                let middle = positions.len() / 2;
+
+                if positions.len() % 2 == 1 {
+                    positions[middle]
+                } else {
+                    (positions[middle - 1] + positions[middle]) / 2.0
+                }
+            }
+        })
+    }
+}
+
+/// Orders each row to minimise crossing links & places technologies close to their neighbours.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LayeredLayout {
+    heuristic: CrossingHeuristic,
+}
+
+impl LayeredLayout {
+    pub fn new(heuristic: CrossingHeuristic) -> Self {
+        LayeredLayout { heuristic }
+    }
+}
+
+impl Layout for LayeredLayout {
+    fn calculate_grid(&self, tree: &TechnologyTree, sizes: &[(u32, u32)], padding: u32) -> Grid {
+        let rows = order_rows(tree, &calculate_rows(tree), self.heuristic);
+        let rows = apply_column_pins(tree, &rows);
+        let row_sizes = calculate_row_sizes(&rows, sizes);
+        place_rows(tree, &rows, &row_sizes, padding)
+    }
+}
+
+/// The rows of the layout with dummy nodes for links spanning multiple rows.
+struct Layers {
+    /// The technology of each node or [`None`] for dummy nodes.
+    nodes: Vec<Option<TechnologyId>>,
+    rows: Vec<Vec<usize>>,
+    upper: Vec<Vec<usize>>,
+    lower: Vec<Vec<usize>>,
+}
+
+impl Layers {
+    fn new(tree: &TechnologyTree, rows: &[Vec<TechnologyId>]) -> Self {
+        let mut layers = Layers {
+            nodes: Vec::new(),
+            rows: vec![Vec::new(); rows.len()],
+            upper: Vec::new(),
+            lower: Vec::new(),
+        };
+        let mut node_of_technology = vec![0; tree.technologies().len()];
+        let mut row_of_technology = vec![0; tree.technologies().len()];
+
+        for (row_index, row) in rows.iter().enumerate() {
+            for id in row {
+                node_of_technology[id.id()] = layers.add_node(Some(*id), row_index);
+                row_of_technology[id.id()] = row_index;
+            }
+        }
+
+        for row in rows {
+            for id in row {
+                for predecessor in tree.get(*id).unwrap().predecessors() {
+                    let (mut start, mut end) = (*predecessor, *id);
+
+                    // Links to the same row are ignored & links upwards are reversed.
+                    match row_of_technology[start.id()].cmp(&row_of_technology[end.id()]) {
+                        std::cmp::Ordering::Equal => continue,
+                        std::cmp::Ordering::Greater => std::mem::swap(&mut start, &mut end),
+                        std::cmp::Ordering::Less => {}
+                    }
+
+                    let mut previous = node_of_technology[start.id()];
+
+                    for row_index in
+                        (row_of_technology[start.id()] + 1)..row_of_technology[end.id()]
+                    {
+                        let dummy = layers.add_node(None, row_index);
+                        layers.add_edge(previous, dummy);
+                        previous = dummy;
+                    }
+
+                    layers.add_edge(previous, node_of_technology[end.id()]);
+                }
+            }
+        }
+
+        layers
+    }
+
+    fn add_node(&mut self, id: Option<TechnologyId>, row_index: usize) -> usize {
+        let node = self.nodes.len();
+        self.nodes.push(id);
+        self.upper.push(Vec::new());
+        self.lower.push(Vec::new());
+        self.rows[row_index].push(node);
+        node
+    }
+
+    fn add_edge(&mut self, upper: usize, lower: usize) {
+        self.upper[lower].push(upper);
+        self.lower[upper].push(lower);
+    }
+
+    fn get_positions(&self) -> Vec<usize> {
+        let mut positions = vec![0; self.nodes.len()];
+
+        for row in &self.rows {
+            for (position, node) in row.iter().enumerate() {
+                positions[*node] = position;
+            }
+        }
+
+        positions
+    }
+
+    /// Sorts a row by the positions of the neighbours of each node.
+    /// Nodes without neighbours keep their position.
+    fn sort_row(&mut self, row_index: usize, is_downwards: bool, heuristic: CrossingHeuristic) {
+        let positions = self.get_positions();
+        let neighbours = if is_downwards {
+            &self.upper
+        } else {
+            &self.lower
+        };
+        let mut keys: Vec<(f64, usize)> = self.rows[row_index]
+            .iter()
+            .enumerate()
+            .map(|(position, node)| {
+                let neighbour_positions = neighbours[*node]
+                    .iter()
+                    .map(|neighbour| positions[*neighbour] as f64)
+                    .collect();
+                let key = heuristic
+                    .calculate(neighbour_positions)
+                    .unwrap_or(position as f64);
+                (key, *node)
+            })
+            .collect();
+
+        keys.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        self.rows[row_index] = keys.into_iter().map(|(_, node)| node).collect();
+    }
+
+    fn count_crossings(&self) -> usize {
+        let positions = self.get_positions();
+        let mut crossings = 0;
+
+        for row in &self.rows {
+            let edges: Vec<(usize, usize)> = row
+                .iter()
+                .flat_map(|upper| {
+                    self.lower[*upper]
+                        .iter()
+                        .map(|lower| (positions[*upper], positions[*lower]))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+
+            for (i, (upper0, lower0)) in edges.iter().enumerate() {
+                for (upper1, lower1) in &edges[i + 1..] {
+                    if (upper0 < upper1 && lower0 > lower1) || (upper0 > upper1 && lower0 < lower1)
+                    {
+                        crossings += 1;
+                    }
+                }
+            }
+        }
+
+        crossings
+    }
+
+    fn get_technology_rows(&self) -> Vec<Vec<TechnologyId>> {
+        self.rows
+            .iter()
+            .map(|row| row.iter().filter_map(|node| self.nodes[*node]).collect())
+            .collect()
+    }
+}
+
+/// Orders the technologies of each row to minimise the crossings of their links.
+/// Links spanning multiple rows are handled by dummy nodes in the rows between.
+pub fn order_rows(
+    tree: &TechnologyTree,
+    rows: &[Vec<TechnologyId>],
+    heuristic: CrossingHeuristic,
+) -> Vec<Vec<TechnologyId>> {
+    let mut layers = Layers::new(tree, rows);
+    let mut best_rows = layers.rows.clone();
+    let mut best_crossings = layers.count_crossings();
+
+    for iteration in 0..ORDERING_ITERATIONS {
+        if best_crossings == 0 {
+            break;
+        }
+
+        let is_downwards = iteration % 2 == 0;
+        let row_indices: Vec<usize> = if is_downwards {
+            (1..layers.rows.len()).collect()
+        } else {
+            (0..layers.rows.len().saturating_sub(1)).rev().collect()
+        };
+
+        for row_index in row_indices {
+            layers.sort_row(row_index, is_downwards, heuristic);
+        }
+
+        let crossings = layers.count_crossings();
+
+        if crossings < best_crossings {
+            best_crossings = crossings;
+            best_rows = layers.rows.clone();
+        }
+    }
+
+    layers.rows = best_rows;
+    layers.get_technology_rows()
+}
+
+/// Places the ordered rows, so that each technology is close to its predecessors & successors.
+pub fn place_rows(
+    tree: &TechnologyTree,
+    rows: &[Vec<TechnologyId>],
+    sizes: &[Vec<(u32, u32)>],
+    padding: u32,
+) -> Grid {
+    let len = tree.technologies().len();
+    let mut centers = vec![0.0; len];
+
+    // Start with rows packed from the left
+    for (row, row_sizes) in izip!(rows, sizes) {
+        let mut x = 0.0;
+
+        for (id, (width, _)) in izip!(row, row_sizes) {
+            let padded_width = (width + 2 * padding) as f64;
+            centers[id.id()] = x + padded_width / 2.0;
+            x += padded_width;
+        }
+    }
+
+    for iteration in 0..PLACEMENT_ITERATIONS {
+        let is_downwards = iteration % 2 == 0;
+
+        for (row, row_sizes) in izip!(rows, sizes) {
+            let desired: Vec<f64> = row
+                .iter()
+                .map(|id| {
+                    let technology = tree.get(*id).unwrap();
+                    let neighbours = if is_downwards {
+                        technology.predecessors()
+                    } else {
+                        technology.successors()
+                    };
+                    CrossingHeuristic::Barycenter
+                        .calculate(neighbours.iter().map(|n| centers[n.id()]).collect())
+                        .unwrap_or(centers[id.id()])
+                })
+                .collect();
+            let gaps: Vec<f64> = row_sizes
+                .iter()
+                .enumerate()
+                .map(|(i, (width, _))| match i {
+                    0 => 0.0,
+                    _ => (row_sizes[i - 1].0 + width) as f64 / 2.0 + 2.0 * padding as f64,
+                })
+                .collect();
+
+            for (id, center) in izip!(row, place_with_gaps(&desired, &gaps)) {
+                centers[id.id()] = center;
+            }
+        }
+    }
+
+    create_grid(rows, sizes, padding, &centers)
+}
+
+/// Finds the positions closest to the desired ones, that keep the minimum gap to the previous position.
+/// The gaps are subtracted, so the problem becomes an isotonic regression solved by pool adjacent violators.
+fn place_with_gaps(desired: &[f64], gaps: &[f64]) -> Vec<f64> {
+    let mut offsets = Vec::with_capacity(gaps.len());
+    let mut offset = 0.0;
+
+    for gap in gaps {
+        offset += gap;
+        offsets.push(offset);
+    }
+
+    // Each block contains the sum & the number of pooled values.
+    let mut blocks: Vec<(f64, usize)> = Vec::new();
+
+    for (value, offset) in izip!(desired, &offsets) {
+        blocks.push((value - offset, 1));
+
+        while blocks.len() > 1 {
+            let (sum1, count1) = blocks[blocks.len() - 1];
+            let (sum0, count0) = blocks[blocks.len() - 2];
+
+            if sum0 / count0 as f64 <= sum1 / count1 as f64 {
+                break;
+            }
+
+            blocks.pop();
+            *blocks.last_mut().unwrap() = (sum0 + sum1, count0 + count1);
+        }
+    }
+
+    let values = blocks
+        .into_iter()
+        .flat_map(|(sum, count)| std::iter::repeat_n(sum / count as f64, count));
+
+    izip!(values, offsets)
+        .map(|(value, offset)| value + offset)
+        .collect()
+}
+
+fn create_grid(
+    rows: &[Vec<TechnologyId>],
+    sizes: &[Vec<(u32, u32)>],
+    padding: u32,
+    centers: &[f64],
+) -> Grid {
+    let min_left = izip!(rows, sizes)
+        .flat_map(|(row, row_sizes)| {
+            izip!(row, row_sizes)
+                .map(|(id, (width, _))| centers[id.id()] - (width / 2 + padding) as f64)
+                .collect::<Vec<_>>()
+        })
+        .fold(f64::INFINITY, f64::min);
+    let mut cells = Vec::new();
+    let mut max_width = 0;
+    let mut y = 0;
+
+    for (row, row_sizes) in izip!(rows, sizes) {
+        let mut row_height = 0;
+
+        for (id, (width, height)) in izip!(row, row_sizes) {
+            let padded_height = height + 2 * padding;
+            let center_x = (centers[id.id()] - min_left).round() as u32;
+
+            cells.push(GridCell::new(
+                *id,
+                center_x,
+                y + padded_height / 2,
+                width / 2,
+                height / 2,
+            ));
+
+            max_width = max_width.max(center_x + width / 2 + padding);
+            row_height = padded_height;
+        }
+
+        y += row_height;
+    }
+
+    Grid::new(max_width, y, cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    #[test]
+    fn test_order_rows() {
+        let tree = create_crossing_tree();
+        let rows = calculate_rows(&tree);
+
+        assert_eq!(Layers::new(&tree, &rows).count_crossings(), 1);
+
+        for heuristic in [CrossingHeuristic::Barycenter, CrossingHeuristic::Median] {
+            let ordered = order_rows(&tree, &rows, heuristic);
+            assert_eq!(Layers::new(&tree, &ordered).count_crossings(), 0);
+        }
+    }
+
+    #[test]
+    fn test_dummy_nodes() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("b", vec!["a"]),
+            Input::test("c", vec!["b", "a"]),
+        ])
+        .unwrap();
+        let layers = Layers::new(&tree, &calculate_rows(&tree));
+
+        assert_eq!(layers.nodes.len(), 4);
+        assert_eq!(layers.rows[1].len(), 2);
+        assert_eq!(layers.get_technology_rows()[1], vec![TechnologyId::new(1)]);
+    }
+
+    #[test]
+    fn test_heuristic() {
+        assert_eq!(CrossingHeuristic::Barycenter.calculate(vec![]), None);
+        assert_eq!(
+            CrossingHeuristic::Barycenter.calculate(vec![0.0, 1.0, 5.0]),
+            Some(2.0)
+        );
+        assert_eq!(
+            CrossingHeuristic::Median.calculate(vec![5.0, 0.0, 1.0]),
+            Some(1.0)
+        );
+        assert_eq!(
+            CrossingHeuristic::Median.calculate(vec![0.0, 1.0, 5.0, 7.0]),
+            Some(3.0)
+        );
+    }
+
+    #[test]
+    fn test_place_with_gaps() {
+        assert_eq!(
+            place_with_gaps(&[0.0, 10.0, 40.0], &[0.0, 5.0, 5.0]),
+            vec![0.0, 10.0, 40.0]
+        );
+        assert_eq!(
+            place_with_gaps(&[10.0, 10.0, 10.0], &[0.0, 10.0, 10.0]),
+            vec![0.0, 10.0, 20.0]
+        );
+    }
+
+    #[test]
+    fn test_place_rows() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("b", vec![]),
+            Input::test("c", vec!["b"]),
+        ])
+        .unwrap();
+        let rows = calculate_rows(&tree);
+        let sizes = vec![vec![(10, 10), (10, 10)], vec![(10, 10)]];
+
+        let grid = place_rows(&tree, &rows, &sizes, 5);
+
+        assert_eq!(grid.width(), 40);
+        assert_eq!(grid.height(), 40);
+        assert_eq!(grid.get_cell(TechnologyId::new(0)).unwrap().center_x, 10);
+        assert_eq!(grid.get_cell(TechnologyId::new(1)).unwrap().center_x, 30);
+        assert_eq!(grid.get_cell(TechnologyId::new(2)).unwrap().center_x, 30);
+    }
+
+    /// `c` needs `b` & `d` needs `a`, which cross in id order.
+    fn create_crossing_tree() -> TechnologyTree {
+        create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("b", vec![]),
+            Input::test("c", vec!["b"]),
+            Input::test("d", vec!["a"]),
+        ])
+        .unwrap()
+    }
+}
@@ -0,0 +1,102 @@
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::TechnologyId;
+use crate::rendering::grid::Grid;
+use crate::usecase::analysis::{calculate_pinned_depth, group_by_depth};
+
+pub mod layered;
+pub mod simple;
+
+/// Arranges the technologies of a tree in a [`Grid`].
+pub trait Layout {
+    /// The sizes of the technologies are indexed by id and don't include the padding around each box.
+    fn calculate_grid(&self, tree: &TechnologyTree, sizes: &[(u32, u32)], padding: u32) -> Grid;
+}
+
+/// Groups the technologies into rows by their [pinned depth](calculate_pinned_depth).
+pub fn calculate_rows(tree: &TechnologyTree) -> Vec<Vec<TechnologyId>> {
+    group_by_depth(&calculate_pinned_depth(tree))
+}
+
+/// Moves technologies with a pinned column to that position inside their row.
+/// The other technologies keep their order & fill the remaining positions.
+pub fn apply_column_pins(
+    tree: &TechnologyTree,
+    rows: &[Vec<TechnologyId>],
+) -> Vec<Vec<TechnologyId>> {
+    rows.iter()
+        .map(|row| {
+            let get_column = |id: &TechnologyId| tree.get(*id).unwrap().pin().column();
+            let mut pinned: Vec<(u32, TechnologyId)> = row
+                .iter()
+                .filter_map(|id| get_column(id).map(|column| (column, *id)))
+                .collect();
+            pinned.sort_by_key(|(column, _)| *column);
+            let mut pinned = pinned.into_iter().peekable();
+            let mut unpinned = row.iter().filter(|id| get_column(id).is_none()).copied();
+            let mut result = Vec::with_capacity(row.len());
+
+            while result.len() < row.len() {
+                let next = match pinned.peek() {
+                    Some((column, _)) if *column as usize <= result.len() => pinned.next(),
+                    _ => None,
+                };
+
+                match next.map(|(_, id)| id).or_else(|| unpinned.next()) {
+                    Some(id) => result.push(id),
+                    None => result.extend(pinned.by_ref().map(|(_, id)| id)),
+                }
+            }
+
+            result
+        })
+        .collect()
+}
+
+/// Returns the size of each technology per row. All technologies of a row get the height of the highest one.
+pub fn calculate_row_sizes(
+    rows: &[Vec<TechnologyId>],
+    sizes: &[(u32, u32)],
+) -> Vec<Vec<(u32, u32)>> {
+    rows.iter()
+        .map(|row| {
+            let max_height = row
+                .iter()
+                .map(|id| sizes[id.id()].1)
+                .max()
+                .unwrap_or_default();
+
+            row.iter()
+                .map(|id| (sizes[id.id()].0, max_height))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::pin::Pin;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    #[test]
+    fn test_pins() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("b", vec![]).with_pin(Pin::new(None, Some(2))),
+            Input::test("c", vec![]),
+            Input::test("d", vec![]).with_pin(Pin::new(None, Some(0))),
+            Input::test("e", vec![]).with_pin(Pin::new(Some(1), Some(7))),
+            Input::test("f", vec!["a"]),
+        ])
+        .unwrap();
+
+        let rows = apply_column_pins(&tree, &calculate_rows(&tree));
+
+        assert_eq!(rows, vec![ids(vec![3, 0, 1, 2]), ids(vec![5, 4])]);
+    }
+
+    fn ids(ids: Vec<usize>) -> Vec<TechnologyId> {
+        ids.into_iter().map(TechnologyId::new).collect()
+    }
+}
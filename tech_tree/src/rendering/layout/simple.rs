@@ -0,0 +1,46 @@
+use crate::model::technology::tree::TechnologyTree;
+use crate::rendering::grid::{Grid, GridCell};
+use crate::rendering::layout::{apply_column_pins, calculate_row_sizes, calculate_rows, Layout};
+use itertools::izip;
+
+/// Groups the technologies into rows by depth, orders each row by id & packs it from the left.
+/// Pinned technologies are moved to their position.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SimpleLayout;
+
+impl Layout for SimpleLayout {
+    fn calculate_grid(&self, tree: &TechnologyTree, sizes: &[(u32, u32)], padding: u32) -> Grid {
+        let rows = apply_column_pins(tree, &calculate_rows(tree));
+        let row_sizes = calculate_row_sizes(&rows, sizes);
+        let mut cells = Vec::new();
+
+        let mut max_width = 0;
+        let mut y = 0;
+
+        for (row, row_size) in izip!(rows, row_sizes) {
+            let mut x = 0;
+            let mut row_height = 0;
+
+            for (id, (width, height)) in izip!(row, row_size) {
+                let padded_width = width + 2 * padding;
+                let padded_height = height + 2 * padding;
+
+                cells.push(GridCell::new(
+                    id,
+                    x + padded_width / 2,
+                    y + padded_height / 2,
+                    width / 2,
+                    height / 2,
+                ));
+
+                x += padded_width;
+                row_height = padded_height;
+            }
+
+            max_width = max_width.max(x);
+            y += row_height;
+        }
+
+        Grid::new(max_width, y, cells)
+    }
+}
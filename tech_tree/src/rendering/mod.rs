@@ -1,3 +1,7 @@
 pub mod grid;
+pub mod layout;
+pub mod orientation;
 pub mod renderer;
+pub mod routing;
+pub mod text;
 pub mod tree;
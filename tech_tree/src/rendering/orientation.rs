@@ -0,0 +1,133 @@
+use crate::rendering::renderer::{Renderer, TechnologyBox};
+
+/// The direction from the technologies without predecessors to their successors.
+///
+/// Layouts always calculate a top-down grid, which is rotated or mirrored afterwards.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Orientation {
+    #[default]
+    TopDown,
+    LeftRight,
+    BottomUp,
+    RightLeft,
+}
+
+impl Orientation {
+    /// Do the links flow along the x-axis?
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Orientation::LeftRight | Orientation::RightLeft)
+    }
+
+    /// Converts between a size in the final image and a size in the top-down layout.
+    pub fn transform_size(&self, (width, height): (u32, u32)) -> (u32, u32) {
+        if self.is_horizontal() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Converts a point of a top-down layout with the given height into the final image.
+    pub fn transform_point(&self, (x, y): (u32, u32), height: u32) -> (u32, u32) {
+        match self {
+            Orientation::TopDown => (x, y),
+            Orientation::LeftRight => (y, x),
+            Orientation::BottomUp => (x, height.saturating_sub(y)),
+            Orientation::RightLeft => (height.saturating_sub(y), x),
+        }
+    }
+}
+
+/// Renders a top-down layout with another orientation.
+pub(crate) struct OrientedRenderer<'a> {
+    renderer: &'a mut dyn Renderer,
+    orientation: Orientation,
+    /// The height of the top-down layout.
+    height: u32,
+}
+
+impl<'a> OrientedRenderer<'a> {
+    pub(crate) fn new(renderer: &'a mut dyn Renderer, orientation: Orientation) -> Self {
+        OrientedRenderer {
+            renderer,
+            orientation,
+            height: 0,
+        }
+    }
+
+    fn transform(&self, point: (u32, u32)) -> (u32, u32) {
+        self.orientation.transform_point(point, self.height)
+    }
+}
+
+impl Renderer for OrientedRenderer<'_> {
+    fn init(&mut self, width: u32, height: u32) {
+        self.height = height;
+        let (width, height) = self.orientation.transform_size((width, height));
+        self.renderer.init(width, height);
+    }
+
+    fn render_link(&mut self, points: Vec<(u32, u32)>, is_dimmed: bool) {
+        let points = points.into_iter().map(|p| self.transform(p)).collect();
+        self.renderer.render_link(points, is_dimmed);
+    }
+
+    fn render_junction(&mut self, x: u32, y: u32, is_any: bool) {
+        let (x, y) = self.transform((x, y));
+        self.renderer.render_junction(x, y, is_any);
+    }
+
+    fn render_era(&mut self, name: &str, x: u32, y: u32, width: u32, height: u32) {
+        let (x0, y0) = self.transform((x, y));
+        let (x1, y1) = self.transform((x + width, y + height));
+        self.renderer.render_era(
+            name,
+            x0.min(x1),
+            y0.min(y1),
+            x0.abs_diff(x1),
+            y0.abs_diff(y1),
+        );
+    }
+
+    fn render_technology(&mut self, technology: &TechnologyBox) {
+        let (x, y) = self.transform((technology.x, technology.y));
+        let (width, height) = self
+            .orientation
+            .transform_size((technology.width, technology.height));
+
+        self.renderer.render_technology(&TechnologyBox {
+            x,
+            y,
+            width,
+            height,
+            ..*technology
+        });
+    }
+
+    fn get_size_of_technology(&self, text: &str, has_icon: bool) -> (u32, u32) {
+        let size = self.renderer.get_size_of_technology(text, has_icon);
+        self.orientation.transform_size(size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Orientation::*;
+
+    #[test]
+    fn test_transform_size() {
+        assert_eq!(TopDown.transform_size((1, 2)), (1, 2));
+        assert_eq!(LeftRight.transform_size((1, 2)), (2, 1));
+        assert_eq!(BottomUp.transform_size((1, 2)), (1, 2));
+        assert_eq!(RightLeft.transform_size((1, 2)), (2, 1));
+    }
+
+    #[test]
+    fn test_transform_point() {
+        assert_eq!(TopDown.transform_point((1, 2), 10), (1, 2));
+        assert_eq!(LeftRight.transform_point((1, 2), 10), (2, 1));
+        assert_eq!(BottomUp.transform_point((1, 2), 10), (1, 8));
+        assert_eq!(RightLeft.transform_point((1, 2), 10), (8, 1));
+    }
+}
@@ -1,13 +1,47 @@
+use crate::model::technology::research::ResearchStatus;
+use crate::model::technology::TechnologyId;
+
+/// The box of a technology, which is centered at its position.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TechnologyBox<'a> {
+    /// The id inside the rendered tree, which differs from the original one for collapsed ranks.
+    pub id: TechnologyId,
+    /// All predecessors inside the rendered tree.
+    pub predecessors: &'a [TechnologyId],
+    pub text: &'a str,
+    pub x: u32,
+    pub y: u32,
+    /// At least the size from [`Renderer::get_size_of_technology`].
+    /// All boxes of a row share the same height.
+    pub width: u32,
+    pub height: u32,
+    /// Only known, if the tree is rendered together with a research state.
+    pub status: Option<ResearchStatus>,
+    pub category: Option<&'a str>,
+    /// Can be shown as a tooltip.
+    pub description: Option<&'a str>,
+    /// The path of an image file, which is shown next to the text.
+    pub icon: Option<&'a str>,
+}
+
 pub trait Renderer {
     /// Initializes the render target.
     fn init(&mut self, width: u32, height: u32);
 
-    /// Renders a link between 2 technologies.
-    fn render_link(&mut self, points: Vec<(u32, u32)>);
+    /// Renders a link between 2 technologies. Links into locked technologies are dimmed.
+    fn render_link(&mut self, points: Vec<(u32, u32)>, is_dimmed: bool);
+
+    /// Renders a junction, which combines the links of a group of predecessors.
+    /// OR-groups are visually distinct from AND-groups.
+    fn render_junction(&mut self, x: u32, y: u32, is_any: bool);
+
+    /// Renders the labelled background band of an era, which is rendered before its technologies.
+    fn render_era(&mut self, name: &str, x: u32, y: u32, width: u32, height: u32);
 
-    /// Renders a technology.
-    fn render_technology(&mut self, text: &str, x: u32, y: u32);
+    /// Renders a technology. Its text can be wrapped into multiple lines.
+    fn render_technology(&mut self, technology: &TechnologyBox);
 
-    /// Returns the size of a technology. Needed for calculating the layout.
-    fn get_size_of_technology(&self, text: &str) -> (u32, u32);
+    /// Returns the size of a technology including all lines of its text & the optional icon.
+    /// Needed for calculating the layout.
+    fn get_size_of_technology(&self, text: &str, has_icon: bool) -> (u32, u32);
 }
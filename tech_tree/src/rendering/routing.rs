@@ -0,0 +1,362 @@
+use crate::model::technology::TechnologyId;
+use crate::rendering::grid::{Grid, GridCell};
+use std::collections::HashMap;
+
+/// How the links between technologies are drawn.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum EdgeRouting {
+    /// Straight lines, which may cross boxes.
+    #[default]
+    Direct,
+    /// Horizontal & vertical segments around boxes. See [`route_orthogonally`].
+    Orthogonal,
+}
+
+/// A link from a technology to a point above another technology, like a junction or the top of its box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LinkRequest {
+    pub source: TechnologyId,
+    pub target: TechnologyId,
+    pub end: (u32, u32),
+}
+
+/// A link passing a free space: the link, the index of the skipped row & the desired x position.
+type Passage = (usize, usize, u32);
+
+/// Routes links in a top-down grid with horizontal & vertical segments only.
+///
+/// Links run horizontally in the gaps between the rows of boxes.
+/// A link skipping rows passes them through the free space between boxes,
+/// like a dummy node in each skipped row.
+/// Parallel segments in the same gap or free space are spread into separate channels.
+pub fn route_orthogonally(grid: &Grid, requests: &[LinkRequest]) -> Vec<Vec<(u32, u32)>> {
+    let bands = calculate_bands(grid);
+    let free_spaces: Vec<Vec<(u32, u32)>> = (0..bands.len())
+        .map(|band| calculate_free_spaces(grid, &bands, band))
+        .collect();
+
+    // The gaps & the free spaces passed by each link
+    let mut gap_lists = Vec::new();
+    let mut passages: HashMap<(usize, usize), Vec<Passage>> = HashMap::new();
+
+    for (link, request) in requests.iter().enumerate() {
+        let source = grid.get_cell(request.source).unwrap();
+        let target = grid.get_cell(request.target).unwrap();
+        let start_gap = find_band(&bands, source) + 1;
+        let end_gap = find_band(&bands, target);
+        let gaps: Vec<usize> = if start_gap <= end_gap {
+            (start_gap..=end_gap).collect()
+        } else {
+            (end_gap..=start_gap).rev().collect()
+        };
+
+        for (index, gaps) in gaps.windows(2).enumerate() {
+            let band = gaps[0].min(gaps[1]);
+            let space = find_closest_space(&free_spaces[band], source.center_x, request.end.0);
+            passages
+                .entry((band, space))
+                .or_default()
+                .push((link, index, request.end.0));
+        }
+
+        gap_lists.push(gaps);
+    }
+
+    // The x positions of each link: the start, one per skipped row & the end
+    let mut x_lists: Vec<Vec<u32>> = requests
+        .iter()
+        .zip(&gap_lists)
+        .map(|(request, gaps)| {
+            let start = grid.get_cell(request.source).unwrap().center_x;
+            let mut x_list = vec![start; gaps.len() + 1];
+            x_list[gaps.len()] = request.end.0;
+            x_list
+        })
+        .collect();
+
+    for ((band, space), mut links) in passages {
+        let (left, right) = free_spaces[band][space];
+        links.sort_by_key(|(link, _, desired_x)| (*desired_x, *link));
+
+        for (x, (link, index, _)) in spread(left, right, links.len()).into_iter().zip(links) {
+            x_lists[link][index + 1] = x;
+        }
+    }
+
+    let mut y_lists = assign_tracks(&bands, grid.height(), &gap_lists, &x_lists);
+
+    // The last horizontal segment must not pass the end, which is above the target
+    for (y_list, request) in y_lists.iter_mut().zip(requests) {
+        if let Some(y) = y_list.last_mut() {
+            *y = (*y).min(request.end.1);
+        }
+    }
+
+    requests
+        .iter()
+        .enumerate()
+        .map(|(link, request)| {
+            let source = grid.get_cell(request.source).unwrap();
+            let mut points = vec![source.get_link_start()];
+
+            for (index, y) in y_lists[link].iter().enumerate() {
+                points.push((x_lists[link][index], *y));
+                points.push((x_lists[link][index + 1], *y));
+            }
+
+            points.push(request.end);
+            points.dedup();
+            points
+        })
+        .collect()
+}
+
+/// Merges the vertical extents of all boxes into bands, which are the rows of the grid.
+fn calculate_bands(grid: &Grid) -> Vec<(u32, u32)> {
+    let mut extents: Vec<(u32, u32)> = grid
+        .cells()
+        .iter()
+        .map(|cell| {
+            (
+                cell.center_y - cell.half_height,
+                cell.center_y + cell.half_height,
+            )
+        })
+        .collect();
+    extents.sort_unstable();
+
+    let mut bands: Vec<(u32, u32)> = Vec::new();
+
+    for (top, bottom) in extents {
+        match bands.last_mut() {
+            Some(band) if top <= band.1 => band.1 = band.1.max(bottom),
+            _ => bands.push((top, bottom)),
+        }
+    }
+
+    bands
+}
+
+fn find_band(bands: &[(u32, u32)], cell: &GridCell) -> usize {
+    bands
+        .iter()
+        .position(|(top, bottom)| *top <= cell.center_y && cell.center_y <= *bottom)
+        .unwrap()
+}
+
+/// Returns the horizontal extents between the boxes of a band.
+fn calculate_free_spaces(grid: &Grid, bands: &[(u32, u32)], band: usize) -> Vec<(u32, u32)> {
+    let mut boxes: Vec<(u32, u32)> = grid
+        .cells()
+        .iter()
+        .filter(|cell| find_band(bands, cell) == band)
+        .map(|cell| {
+            (
+                cell.center_x - cell.half_width,
+                cell.center_x + cell.half_width,
+            )
+        })
+        .collect();
+    boxes.sort_unstable();
+
+    let mut spaces = Vec::new();
+    let mut left = 0;
+
+    for (box_left, box_right) in boxes {
+        if box_left > left {
+            spaces.push((left, box_left));
+        }
+
+        left = left.max(box_right);
+    }
+
+    spaces.push((left, grid.width().max(left)));
+    spaces
+}
+
+/// Finds the free space with the shortest detour between the start & the end of a link.
+fn find_closest_space(spaces: &[(u32, u32)], start_x: u32, end_x: u32) -> usize {
+    let get_distance =
+        |(left, right): (u32, u32), x: u32| left.saturating_sub(x) + x.saturating_sub(right);
+
+    (0..spaces.len())
+        .min_by_key(|index| {
+            get_distance(spaces[*index], start_x) + get_distance(spaces[*index], end_x)
+        })
+        .unwrap()
+}
+
+/// Distributes channels evenly inside an extent, without touching its borders.
+fn spread(start: u32, end: u32, count: usize) -> Vec<u32> {
+    let size = end - start;
+
+    (0..count as u32)
+        .map(|i| start + (i + 1) * size / (count as u32 + 1))
+        .collect()
+}
+
+/// Calculates the y position of the horizontal segment of each link in each of its gaps.
+/// Overlapping segments in the same gap get separate tracks.
+fn assign_tracks(
+    bands: &[(u32, u32)],
+    height: u32,
+    gap_lists: &[Vec<usize>],
+    x_lists: &[Vec<u32>],
+) -> Vec<Vec<u32>> {
+    let mut segments_per_gap: HashMap<usize, Vec<(u32, u32, usize, usize)>> = HashMap::new();
+
+    for (link, (gaps, x_list)) in gap_lists.iter().zip(x_lists).enumerate() {
+        for (index, gap) in gaps.iter().enumerate() {
+            let (x0, x1) = (x_list[index], x_list[index + 1]);
+            segments_per_gap
+                .entry(*gap)
+                .or_default()
+                .push((x0.min(x1), x0.max(x1), link, index));
+        }
+    }
+
+    let mut y_lists: Vec<Vec<u32>> = gap_lists.iter().map(|gaps| vec![0; gaps.len()]).collect();
+
+    for (gap, mut segments) in segments_per_gap {
+        let top = if gap == 0 { 0 } else { bands[gap - 1].1 };
+        let bottom = bands.get(gap).map(|band| band.0).unwrap_or(height);
+
+        segments.sort_unstable();
+
+        // The right end of each track
+        let mut track_ends: Vec<u32> = Vec::new();
+        let mut tracks = Vec::new();
+
+        for (left, right, _, _) in &segments {
+            let track = match track_ends.iter().position(|end| *end < *left) {
+                Some(track) => track,
+                None => {
+                    track_ends.push(0);
+                    track_ends.len() - 1
+                }
+            };
+
+            track_ends[track] = *right;
+            tracks.push(track);
+        }
+
+        let track_ys = spread(top, bottom, track_ends.len());
+
+        for ((_, _, link, index), track) in segments.into_iter().zip(tracks) {
+            y_lists[link][index] = track_ys[track];
+        }
+    }
+
+    y_lists
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `a` & `b` in the first row, `c` in the second row & `d` in the third row.
+    fn create_grid() -> Grid {
+        Grid::new(
+            100,
+            150,
+            vec![
+                GridCell::new(TechnologyId::new(0), 25, 25, 15, 15),
+                GridCell::new(TechnologyId::new(1), 75, 25, 15, 15),
+                GridCell::new(TechnologyId::new(2), 50, 75, 40, 15),
+                GridCell::new(TechnologyId::new(3), 50, 125, 15, 15),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_adjacent_rows() {
+        let request = LinkRequest {
+            source: TechnologyId::new(0),
+            target: TechnologyId::new(2),
+            end: (50, 57),
+        };
+
+        assert_eq!(
+            route_orthogonally(&create_grid(), &[request]),
+            vec![vec![(25, 40), (25, 50), (50, 50), (50, 57)]]
+        );
+    }
+
+    #[test]
+    fn test_end_above_track() {
+        let request = LinkRequest {
+            source: TechnologyId::new(0),
+            target: TechnologyId::new(2),
+            end: (50, 45),
+        };
+
+        assert_eq!(
+            route_orthogonally(&create_grid(), &[request]),
+            vec![vec![(25, 40), (25, 45), (50, 45)]]
+        );
+    }
+
+    #[test]
+    fn test_skipped_row() {
+        let request = LinkRequest {
+            source: TechnologyId::new(0),
+            target: TechnologyId::new(3),
+            end: (50, 107),
+        };
+
+        assert_eq!(
+            route_orthogonally(&create_grid(), &[request]),
+            vec![vec![
+                (25, 40),
+                (25, 50),
+                (5, 50),
+                (5, 100),
+                (50, 100),
+                (50, 107)
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_channels() {
+        let requests = [
+            LinkRequest {
+                source: TechnologyId::new(0),
+                target: TechnologyId::new(3),
+                end: (50, 107),
+            },
+            LinkRequest {
+                source: TechnologyId::new(0),
+                target: TechnologyId::new(3),
+                end: (53, 107),
+            },
+            LinkRequest {
+                source: TechnologyId::new(1),
+                target: TechnologyId::new(3),
+                end: (56, 107),
+            },
+        ];
+
+        let links = route_orthogonally(&create_grid(), &requests);
+
+        // The links of `a` pass the left of `c` in separate channels
+        assert_eq!(links[0][1..5], [(25, 46), (3, 46), (3, 96), (50, 96)]);
+        assert_eq!(links[1][1..5], [(25, 53), (6, 53), (6, 103), (53, 103)]);
+        // The link of `b` passes the right of `c`
+        assert_eq!(links[2][1..5], [(75, 46), (95, 46), (95, 96), (56, 96)]);
+    }
+
+    #[test]
+    fn test_calculate_bands() {
+        assert_eq!(
+            calculate_bands(&create_grid()),
+            vec![(10, 40), (60, 90), (110, 140)]
+        );
+    }
+
+    #[test]
+    fn test_spread() {
+        assert_eq!(spread(0, 100, 1), vec![50]);
+        assert_eq!(spread(0, 90, 2), vec![30, 60]);
+    }
+}
@@ -0,0 +1,58 @@
+/// Splits a text into lines at whitespace, so that each line fits into the maximum width.
+/// Words wider than the maximum width get their own line.
+pub fn wrap_text<F: Fn(&str) -> u32>(text: &str, max_width: u32, get_width: F) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if line.is_empty() {
+            line.push_str(word);
+            continue;
+        }
+
+        let candidate = format!("{} {}", line, word);
+
+        if get_width(&candidate) <= max_width {
+            line = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        }
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap(text: &str, max_width: u32) -> Vec<String> {
+        wrap_text(text, max_width, |line| line.chars().count() as u32)
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap("Advanced Orbital Manufacturing Techniques", 20),
+            vec!["Advanced Orbital", "Manufacturing", "Techniques"]
+        );
+        assert_eq!(wrap("Short name", 20), vec!["Short name"]);
+    }
+
+    #[test]
+    fn test_long_word() {
+        assert_eq!(
+            wrap("a Supercalifragilistic b", 5),
+            vec!["a", "Supercalifragilistic", "b"]
+        );
+    }
+
+    #[test]
+    fn test_empty_text() {
+        assert_eq!(wrap("", 5), vec![""]);
+    }
+}
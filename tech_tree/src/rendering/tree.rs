@@ -1,133 +1,401 @@
+use crate::model::error::AddError;
+use crate::model::technology::prerequisite::Prerequisite;
+use crate::model::technology::research::{ResearchState, ResearchStatus};
 use crate::model::technology::tree::TechnologyTree;
-use crate::model::technology::TechnologyId;
+use crate::model::technology::{Technology, TechnologyId};
 use crate::rendering::grid::{Grid, GridCell};
-use crate::rendering::renderer::Renderer;
-use crate::usecase::analysis::{calculate_depth, group_by_depth};
-use itertools::izip;
+use crate::rendering::layout::simple::SimpleLayout;
+use crate::rendering::layout::Layout;
+use crate::rendering::orientation::{Orientation, OrientedRenderer};
+use crate::rendering::renderer::{Renderer, TechnologyBox};
+use crate::rendering::routing::{route_orthogonally, EdgeRouting, LinkRequest};
+use crate::usecase::ranks::collapse_ranks;
+use crate::usecase::validation::pins::validate_pinned_rows;
+
+/// A link or junction, that is rendered after all technologies.
+enum Shape {
+    Link(Vec<(u32, u32)>, bool),
+    /// A link from a technology with its direct route, that can be replaced by the orthogonal one.
+    Route(LinkRequest, Vec<(u32, u32)>, bool),
+    Junction(u32, u32, bool),
+}
 
 pub struct TreeRenderer {
     padding: u32,
+    show_cost: bool,
+    collapse_ranks: bool,
+    layout: Box<dyn Layout>,
+    orientation: Orientation,
+    routing: EdgeRouting,
+    show_eras: bool,
 }
 
 impl TreeRenderer {
     pub fn new(padding: u32) -> Self {
-        Self { padding }
+        Self {
+            padding,
+            show_cost: false,
+            collapse_ranks: false,
+            layout: Box::new(SimpleLayout),
+            orientation: Orientation::TopDown,
+            routing: EdgeRouting::Direct,
+            show_eras: false,
+        }
+    }
+
+    /// Displays the research cost inside the box of each technology.
+    pub fn with_cost(mut self, show_cost: bool) -> Self {
+        self.show_cost = show_cost;
+        self
+    }
+
+    /// Combines each series of contiguous ranks into a single box. See [`collapse_ranks`].
+    pub fn with_collapsed_ranks(mut self, collapse_ranks: bool) -> Self {
+        self.collapse_ranks = collapse_ranks;
+        self
+    }
+
+    /// Renders a background band behind the technologies of each era.
+    pub fn with_eras(mut self, show_eras: bool) -> Self {
+        self.show_eras = show_eras;
+        self
+    }
+
+    pub fn with_routing(mut self, routing: EdgeRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Replaces the default [`SimpleLayout`].
+    pub fn with_layout<L: Layout + 'static>(mut self, layout: L) -> Self {
+        self.layout = Box::new(layout);
+        self
     }
 
-    pub fn render(&mut self, renderer: &mut dyn Renderer, tree: &TechnologyTree) {
+    /// Renders the tree. The optional [`ResearchState`] allows to visualize the progress of a player.
+    /// Fails, if the ranks should be collapsed, but a predecessor is not part of the tree,
+    /// or if a technology is pinned to a row beyond the number of technologies.
+    pub fn render(
+        &mut self,
+        renderer: &mut dyn Renderer,
+        tree: &TechnologyTree,
+        state: Option<&ResearchState>,
+    ) -> Result<(), AddError> {
+        validate_pinned_rows(tree)?;
+
+        if self.collapse_ranks {
+            let (collapsed, members_list) = collapse_ranks(tree)?;
+            let statuses = state.map(|state| {
+                members_list
+                    .iter()
+                    .map(|members| get_status_of_series(tree, state, members))
+                    .collect()
+            });
+
+            self.render_tree(renderer, &collapsed, statuses);
+        } else {
+            let statuses = state.map(|state| {
+                tree.technologies()
+                    .iter()
+                    .map(|technology| state.get_status(tree, *technology.id()))
+                    .collect()
+            });
+
+            self.render_tree(renderer, tree, statuses);
+        }
+
+        Ok(())
+    }
+
+    fn render_tree(
+        &self,
+        renderer: &mut dyn Renderer,
+        tree: &TechnologyTree,
+        statuses: Option<Vec<ResearchStatus>>,
+    ) {
+        // Everything is calculated top-down & transformed by the renderer.
+        let renderer = &mut OrientedRenderer::new(renderer, self.orientation);
         let grid = self.calculate_grid(renderer, tree);
 
         renderer.init(grid.width(), grid.height());
 
+        if self.show_eras {
+            self.render_eras(renderer, &grid, tree);
+        }
+
+        let mut shapes = Vec::new();
+
         for cell in grid.cells() {
             let technology = tree.get(cell.id).unwrap();
+            let status = statuses.as_ref().map(|statuses| statuses[cell.id.id()]);
+
+            renderer.render_technology(&TechnologyBox {
+                id: cell.id,
+                predecessors: technology.predecessors(),
+                text: &self.get_text(technology),
+                x: cell.center_x,
+                y: cell.center_y,
+                width: 2 * cell.half_width,
+                height: 2 * cell.half_height,
+                status,
+                category: technology.category(),
+                description: technology.description(),
+                icon: technology.icon(),
+            });
+
+            let is_dimmed = status == Some(ResearchStatus::Locked);
+
+            self.render_links(&mut shapes, &grid, cell, technology, is_dimmed);
+        }
 
-            renderer.render_technology(technology.name().get_full(), cell.center_x, cell.center_y);
-
-            if !technology.successors().is_empty() {
-                let link_start = cell.get_link_start();
-                let link_start2 = (link_start.0, link_start.1 + self.padding);
+        self.render_shapes(renderer, &grid, shapes);
+    }
 
-                for successor_id in technology.successors() {
-                    let link_end = self.calculate_link_end(tree, &grid, cell, *successor_id);
-                    let link_end2 = (link_end.0, link_end.1 - self.padding);
-                    let link_end = (link_end.0, link_end.1 - 3);
+    /// Renders a band across the whole tree from the highest to the lowest technology of each era.
+    /// The layouts keep eras in separate rows, so the bands only overlap due to pinned rows.
+    /// Eras without technologies are skipped.
+    fn render_eras(&self, renderer: &mut dyn Renderer, grid: &Grid, tree: &TechnologyTree) {
+        for era in tree.eras() {
+            let extents = grid
+                .cells()
+                .iter()
+                .filter(|cell| tree.get(cell.id).unwrap().era() == Some(era.as_str()))
+                .map(|cell| {
+                    (
+                        cell.center_y - cell.half_height,
+                        cell.center_y + cell.half_height,
+                    )
+                });
+            let (top, bottom) = match extents
+                .reduce(|(top0, bottom0), (top1, bottom1)| (top0.min(top1), bottom0.max(bottom1)))
+            {
+                Some(extent) => extent,
+                None => continue,
+            };
+            let top = top.saturating_sub(self.padding / 2);
+            let bottom = (bottom + self.padding / 2).min(grid.height());
+
+            renderer.render_era(era, 0, top, grid.width(), bottom - top);
+        }
+    }
 
-                    renderer.render_link(vec![link_start, link_start2, link_end2, link_end]);
+    /// Renders the links & junctions after all technologies,
+    /// because the orthogonal routing needs to know all links.
+    fn render_shapes(&self, renderer: &mut dyn Renderer, grid: &Grid, shapes: Vec<Shape>) {
+        let mut routes = if self.routing == EdgeRouting::Orthogonal {
+            let requests: Vec<LinkRequest> = shapes
+                .iter()
+                .filter_map(|shape| match shape {
+                    Shape::Route(request, _, _) => Some(*request),
+                    _ => None,
+                })
+                .collect();
+            Some(route_orthogonally(grid, &requests).into_iter())
+        } else {
+            None
+        };
+
+        for shape in shapes {
+            match shape {
+                Shape::Link(points, is_dimmed) => renderer.render_link(points, is_dimmed),
+                Shape::Route(_, points, is_dimmed) => {
+                    let points = match &mut routes {
+                        Some(routes) => routes.next().unwrap(),
+                        None => points,
+                    };
+                    renderer.render_link(points, is_dimmed);
                 }
+                Shape::Junction(x, y, is_any) => renderer.render_junction(x, y, is_any),
             }
         }
     }
-    fn calculate_link_end(
+
+    /// Returns the text displayed inside the box of a technology.
+    fn get_text(&self, technology: &Technology) -> String {
+        let name = technology.name().get_full();
+
+        if self.show_cost {
+            format!("{} ({})", name, technology.cost())
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Renders the links from the predecessors to a technology.
+    /// Nested groups of the prerequisite and OR-groups are rendered as junctions.
+    fn render_links(
         &self,
-        tree: &TechnologyTree,
+        shapes: &mut Vec<Shape>,
         grid: &Grid,
         cell: &GridCell,
-        successor_id: TechnologyId,
-    ) -> (u32, u32) {
-        let offset = tree
-            .get(successor_id)
-            .and_then(|successor| successor.get_predecessor_index(cell.id))
-            .unwrap_or_default() as u32
-            * 3;
-        let successor_cell = grid.get_cell(successor_id).unwrap();
-        let link_end = successor_cell.get_link_end();
-        (link_end.0 + offset, link_end.1)
-    }
-
-    fn calculate_grid(&self, renderer: &mut dyn Renderer, tree: &TechnologyTree) -> Grid {
-        let depth = calculate_depth(tree);
-        let groups = group_by_depth(&depth);
-        let sizes = self.calculate_sizes(renderer, tree, &groups);
-        let mut cells = Vec::new();
-
-        let mut max_width = 0;
-        let mut y = 0;
-
-        for (column_id, column_size) in izip!(groups, sizes) {
-            let mut x = 0;
-            let mut column_height = 0;
-
-            for (id, (width, height)) in izip!(column_id, column_size) {
-                let padded_width = width + 2 * self.padding;
-                let padded_height = height + 2 * self.padding;
-
-                cells.push(GridCell::new(
-                    id,
-                    x + padded_width / 2,
-                    y + padded_height / 2,
-                    width / 2,
-                    height / 2,
+        technology: &Technology,
+        is_dimmed: bool,
+    ) {
+        let target = cell.get_link_end();
+
+        match technology.prerequisite() {
+            Prerequisite::All(children) => {
+                self.render_children(shapes, grid, technology, children, target, false, is_dimmed)
+            }
+            Prerequisite::Any(children) => {
+                let junction = (target.0, target.1 - self.padding / 2);
+                self.render_junction(
+                    shapes, grid, technology, children, true, junction, is_dimmed,
+                );
+                shapes.push(Shape::Link(
+                    vec![junction, (target.0, target.1 - 3)],
+                    is_dimmed,
                 ));
-
-                x += padded_width;
-                column_height = padded_height;
             }
-
-            max_width = max_width.max(x);
-            y += column_height;
+            prerequisite => self.render_children(
+                shapes,
+                grid,
+                technology,
+                std::slice::from_ref(prerequisite),
+                target,
+                false,
+                is_dimmed,
+            ),
         }
-
-        Grid::new(max_width, y, cells)
     }
 
-    fn calculate_sizes(
+    #[allow(clippy::too_many_arguments)]
+    fn render_junction(
         &self,
-        renderer: &mut dyn Renderer,
-        tree: &TechnologyTree,
-        groups: &[Vec<TechnologyId>],
-    ) -> Vec<Vec<(u32, u32)>> {
-        let mut sizes = Vec::new();
-
-        for column in groups {
-            let mut widths = Vec::new();
-            let mut max_height = 0;
-
-            for id in column {
-                let technology = tree.get(*id).unwrap();
-                let (width, height) = renderer.get_size_of_technology(technology.name().get_full());
-
-                widths.push(width);
-                max_height = max_height.max(height);
-            }
+        shapes: &mut Vec<Shape>,
+        grid: &Grid,
+        technology: &Technology,
+        children: &[Prerequisite<TechnologyId>],
+        is_any: bool,
+        junction: (u32, u32),
+        is_dimmed: bool,
+    ) {
+        self.render_children(
+            shapes, grid, technology, children, junction, true, is_dimmed,
+        );
+        shapes.push(Shape::Junction(junction.0, junction.1, is_any));
+    }
 
-            sizes.push(
-                widths
-                    .into_iter()
-                    .map(|width| (width, max_height))
-                    .collect(),
-            );
+    /// Renders the links from the children of a group to its target,
+    /// which is either a junction or the technology itself.
+    #[allow(clippy::too_many_arguments)]
+    fn render_children(
+        &self,
+        shapes: &mut Vec<Shape>,
+        grid: &Grid,
+        technology: &Technology,
+        children: &[Prerequisite<TechnologyId>],
+        target: (u32, u32),
+        is_junction: bool,
+        is_dimmed: bool,
+    ) {
+        let junction_y = if is_junction {
+            target.1
+        } else {
+            target.1 - self.padding / 2
+        };
+
+        for (index, child) in children.iter().enumerate() {
+            let (group, is_any) = match child {
+                Prerequisite::Technology(id) => {
+                    let start = grid.get_cell(*id).unwrap().get_link_start();
+                    let start2 = (start.0, start.1 + self.padding);
+                    let points = if is_junction {
+                        vec![
+                            start,
+                            start2,
+                            (target.0, junction_y - self.padding / 2),
+                            target,
+                        ]
+                    } else {
+                        // Arrows of multiple predecessors are side by side, but inside the box
+                        let half_width = grid.get_cell(*technology.id()).unwrap().half_width;
+                        let offset =
+                            (technology.get_predecessor_index(*id).unwrap_or_default() as u32 * 3)
+                                .min(half_width.saturating_sub(1));
+                        let end = (target.0 + offset, target.1);
+                        vec![
+                            start,
+                            start2,
+                            (end.0, end.1 - self.padding),
+                            (end.0, end.1 - 3),
+                        ]
+                    };
+
+                    let request = LinkRequest {
+                        source: *id,
+                        target: *technology.id(),
+                        end: *points.last().unwrap(),
+                    };
+                    shapes.push(Shape::Route(request, points, is_dimmed));
+                    continue;
+                }
+                Prerequisite::All(group) => (group, false),
+                Prerequisite::Any(group) => (group, true),
+            };
+
+            let junction_x = (target.0 + (2 * index as u32 + 1) * self.padding / 2)
+                .saturating_sub(children.len() as u32 * self.padding / 2);
+            let junction = (junction_x, junction_y);
+            let end = if is_junction {
+                target
+            } else {
+                (junction_x, target.1 - 3)
+            };
+
+            self.render_junction(shapes, grid, technology, group, is_any, junction, is_dimmed);
+            shapes.push(Shape::Link(vec![junction, end], is_dimmed));
         }
+    }
 
-        sizes
+    fn calculate_grid(&self, renderer: &mut dyn Renderer, tree: &TechnologyTree) -> Grid {
+        let sizes: Vec<(u32, u32)> = tree
+            .technologies()
+            .iter()
+            .map(|technology| {
+                renderer
+                    .get_size_of_technology(&self.get_text(technology), technology.icon().is_some())
+            })
+            .collect();
+
+        self.layout.calculate_grid(tree, &sizes, self.padding)
+    }
+}
+
+/// A series of ranks is researched, if all ranks are.
+/// It is available, if the next rank can be researched.
+fn get_status_of_series(
+    tree: &TechnologyTree,
+    state: &ResearchState,
+    members: &[TechnologyId],
+) -> ResearchStatus {
+    let statuses: Vec<ResearchStatus> = members
+        .iter()
+        .map(|id| state.get_status(tree, *id))
+        .collect();
+
+    if statuses.iter().all(|s| *s == ResearchStatus::Researched) {
+        ResearchStatus::Researched
+    } else if statuses.contains(&ResearchStatus::Available) {
+        ResearchStatus::Available
+    } else {
+        ResearchStatus::Locked
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::technology::pin::Pin;
     use crate::model::technology::Input;
-    use crate::usecase::creation::create_tree;
+    use crate::rendering::grid::GridCell;
+    use crate::rendering::layout::layered::{CrossingHeuristic, LayeredLayout};
+    use crate::usecase::creation::{create_tree, create_tree_with_rank_chaining};
     use std::collections::HashMap;
 
     #[derive(Default)]
@@ -135,6 +403,16 @@ mod tests {
         pub width: u32,
         pub height: u32,
         pub technologies: HashMap<String, (u32, u32)>,
+        pub sizes: HashMap<String, (u32, u32)>,
+        pub statuses: HashMap<String, ResearchStatus>,
+        pub dimmed_links: u32,
+        pub links: Vec<Vec<(u32, u32)>>,
+        pub junctions: Vec<(u32, u32, bool)>,
+        pub eras: Vec<(String, u32, u32, u32, u32)>,
+        pub categories: HashMap<String, String>,
+        pub descriptions: HashMap<String, String>,
+        pub icons: HashMap<String, String>,
+        pub ids: HashMap<String, (TechnologyId, Vec<TechnologyId>)>,
     }
 
     impl Renderer for MockRender {
@@ -143,15 +421,55 @@ mod tests {
             self.height = height;
         }
 
-        fn render_link(&mut self, _points: Vec<(u32, u32)>) {}
+        fn render_link(&mut self, points: Vec<(u32, u32)>, is_dimmed: bool) {
+            if is_dimmed {
+                self.dimmed_links += 1;
+            }
+
+            self.links.push(points);
+        }
+
+        fn render_junction(&mut self, x: u32, y: u32, is_any: bool) {
+            self.junctions.push((x, y, is_any));
+        }
 
-        fn render_technology(&mut self, text: &str, x: u32, y: u32) {
-            self.technologies.insert(text.to_string(), (x, y));
+        fn render_era(&mut self, name: &str, x: u32, y: u32, width: u32, height: u32) {
+            self.eras.push((name.to_string(), x, y, width, height));
         }
 
-        fn get_size_of_technology(&self, text: &str) -> (u32, u32) {
+        fn render_technology(&mut self, technology: &TechnologyBox) {
+            let text = technology.text.to_string();
+            self.ids.insert(
+                text.clone(),
+                (technology.id, technology.predecessors.to_vec()),
+            );
+            self.technologies
+                .insert(text.clone(), (technology.x, technology.y));
+            self.sizes
+                .insert(text.clone(), (technology.width, technology.height));
+
+            if let Some(category) = technology.category {
+                self.categories.insert(text.clone(), category.to_string());
+            }
+
+            if let Some(icon) = technology.icon {
+                self.icons.insert(text.clone(), icon.to_string());
+            }
+
+            if let Some(description) = technology.description {
+                self.descriptions
+                    .insert(text.clone(), description.to_string());
+            }
+
+            if let Some(status) = technology.status {
+                self.statuses.insert(text, status);
+            }
+        }
+
+        fn get_size_of_technology(&self, text: &str, has_icon: bool) -> (u32, u32) {
             let l = text.len() as u32;
-            (l * 10, l * 20)
+            let icon_width = if has_icon { 10 } else { 0 };
+            (l * 10 + icon_width, l * 20)
         }
     }
 
@@ -161,7 +479,7 @@ mod tests {
         let mut renderer = MockRender::default();
         let mut tree_renderer = TreeRenderer::new(5);
 
-        tree_renderer.render(&mut renderer, &tree);
+        tree_renderer.render(&mut renderer, &tree, None).unwrap();
 
         assert_eq!(renderer.width, 70);
         assert_eq!(renderer.height, 100);
@@ -173,6 +491,313 @@ mod tests {
                 ("ccc".to_string(), (50, 65)),
             ])
         );
+        // All boxes of a row share the same height
+        assert_eq!(
+            renderer.sizes,
+            HashMap::from([
+                ("a".to_string(), (10, 20)),
+                ("bb".to_string(), (20, 60)),
+                ("ccc".to_string(), (30, 60)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_render_with_invalid_row() {
+        let mut technologies = init_tree().technologies().to_vec();
+        technologies[0] = technologies[0]
+            .clone()
+            .with_pin(Pin::new(Some(u32::MAX), None));
+        let tree = TechnologyTree::new(technologies);
+        let mut renderer = MockRender::default();
+
+        assert_eq!(
+            TreeRenderer::new(5).render(&mut renderer, &tree, None),
+            Err(AddError::InvalidRow("a".to_string(), u32::MAX, 3))
+        );
+    }
+
+    #[test]
+    fn test_render_with_cost() {
+        let tree = create_tree(vec![Input::with_cost("a", vec![], 7)]).unwrap();
+        let mut renderer = MockRender::default();
+        let mut tree_renderer = TreeRenderer::new(5).with_cost(true);
+
+        tree_renderer.render(&mut renderer, &tree, None).unwrap();
+
+        assert_eq!(renderer.width, 60);
+        assert_eq!(renderer.height, 110);
+        assert_eq!(
+            renderer.technologies,
+            HashMap::from([("a (7)".to_string(), (30, 55))])
+        );
+    }
+
+    #[test]
+    fn test_render_with_state() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("b", vec!["a"]),
+            Input::test("c", vec!["b"]),
+            Input::test("d", vec!["a", "c"]),
+        ])
+        .unwrap();
+        let mut state = ResearchState::new(&tree);
+        state.research(&tree, TechnologyId::new(0)).unwrap();
+        let mut renderer = MockRender::default();
+        let mut tree_renderer = TreeRenderer::new(5);
+
+        tree_renderer
+            .render(&mut renderer, &tree, Some(&state))
+            .unwrap();
+
+        assert_eq!(
+            renderer.statuses,
+            HashMap::from([
+                ("a".to_string(), ResearchStatus::Researched),
+                ("b".to_string(), ResearchStatus::Available),
+                ("c".to_string(), ResearchStatus::Locked),
+                ("d".to_string(), ResearchStatus::Locked),
+            ])
+        );
+        assert_eq!(renderer.dimmed_links, 3);
+    }
+
+    #[test]
+    fn test_render_with_any() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("b", vec![]),
+            Input::test("c", vec![]),
+            Input::with_prerequisite(
+                "d",
+                Prerequisite::Any(vec![
+                    Prerequisite::all(vec!["a", "b"]),
+                    Prerequisite::Technology("c"),
+                ]),
+            ),
+        ])
+        .unwrap();
+        let mut renderer = MockRender::default();
+        let mut tree_renderer = TreeRenderer::new(10);
+
+        tree_renderer.render(&mut renderer, &tree, None).unwrap();
+
+        assert_eq!(renderer.junctions, vec![(10, 45, false), (15, 45, true)]);
+        assert_eq!(
+            renderer.links,
+            vec![
+                vec![(15, 30), (15, 40), (10, 40), (10, 45)],
+                vec![(45, 30), (45, 40), (10, 40), (10, 45)],
+                vec![(10, 45), (15, 45)],
+                vec![(75, 30), (75, 40), (15, 40), (15, 45)],
+                vec![(15, 45), (15, 47)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_with_collapsed_ranks() {
+        let tree = create_tree_with_rank_chaining(vec![
+            Input::test("a", vec![]),
+            Input::test("b 1", vec!["a"]),
+            Input::test("b 2", vec![]),
+            Input::test("b 3", vec![]),
+        ])
+        .unwrap();
+        let mut state = ResearchState::new(&tree);
+        state.research(&tree, TechnologyId::new(0)).unwrap();
+        state.research(&tree, TechnologyId::new(1)).unwrap();
+        let mut renderer = MockRender::default();
+        let mut tree_renderer = TreeRenderer::new(5).with_collapsed_ranks(true);
+
+        tree_renderer
+            .render(&mut renderer, &tree, Some(&state))
+            .unwrap();
+
+        assert_eq!(
+            renderer.statuses,
+            HashMap::from([
+                ("a".to_string(), ResearchStatus::Researched),
+                ("b 1–3".to_string(), ResearchStatus::Available),
+            ])
+        );
+        assert_eq!(renderer.links.len(), 1);
+        // The ids of the collapsed tree
+        assert_eq!(
+            renderer.ids,
+            HashMap::from([
+                ("a".to_string(), (TechnologyId::new(0), vec![])),
+                (
+                    "b 1–3".to_string(),
+                    (TechnologyId::new(1), vec![TechnologyId::new(0)])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_render_with_layered_layout() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("b", vec![]),
+            Input::test("c", vec!["b"]),
+            Input::test("d", vec!["a"]),
+        ])
+        .unwrap();
+        let mut renderer = MockRender::default();
+        let mut tree_renderer =
+            TreeRenderer::new(5).with_layout(LayeredLayout::new(CrossingHeuristic::Median));
+
+        tree_renderer.render(&mut renderer, &tree, None).unwrap();
+
+        assert_eq!(renderer.width, 40);
+        assert_eq!(
+            renderer.technologies,
+            HashMap::from([
+                ("a".to_string(), (10, 15)),
+                ("b".to_string(), (30, 15)),
+                ("d".to_string(), (10, 45)),
+                ("c".to_string(), (30, 45)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_render_left_to_right() {
+        let tree = init_tree();
+        let mut renderer = MockRender::default();
+        let mut tree_renderer = TreeRenderer::new(5).with_orientation(Orientation::LeftRight);
+
+        tree_renderer.render(&mut renderer, &tree, None).unwrap();
+
+        assert_eq!(renderer.width, 60);
+        assert_eq!(renderer.height, 120);
+        assert_eq!(
+            renderer.technologies,
+            HashMap::from([
+                ("a".to_string(), (10, 15)),
+                ("bb".to_string(), (40, 25)),
+                ("ccc".to_string(), (40, 85)),
+            ])
+        );
+        assert_eq!(
+            renderer.links[0],
+            vec![(15, 15), (20, 15), (20, 25), (22, 25)]
+        );
+    }
+
+    #[test]
+    fn test_render_with_orthogonal_routing() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("bb", vec!["a"]),
+            Input::test("c", vec!["bb", "a"]),
+        ])
+        .unwrap();
+        let mut renderer = MockRender::default();
+        let mut tree_renderer = TreeRenderer::new(5).with_routing(EdgeRouting::Orthogonal);
+
+        tree_renderer.render(&mut renderer, &tree, None).unwrap();
+
+        assert_eq!(renderer.links.len(), 3);
+
+        for link in &renderer.links {
+            for (start, end) in link.iter().zip(link.iter().skip(1)) {
+                assert!(start.0 == end.0 || start.1 == end.1);
+            }
+        }
+
+        // The link from `a` to `c` passes `bb` on its left side
+        assert_eq!(
+            renderer.links[2],
+            vec![(10, 25), (10, 28), (2, 28), (2, 78), (13, 78), (13, 82)]
+        );
+    }
+
+    #[test]
+    fn test_render_with_eras() {
+        let era = |era: &str| Some(era.to_string());
+        let tree = create_tree(vec![
+            Input::test("a", vec![]).with_era(era("Ancient")),
+            Input::test("bb", vec!["a"])
+                .with_era(era("Medieval"))
+                .with_category(Some("Military".to_string()))
+                .with_description(Some("Unlocks swords".to_string())),
+            Input::test("c", vec!["bb"]).with_era(era("Medieval")),
+            Input::test("d", vec![]).with_era(era("Medieval")),
+        ])
+        .unwrap()
+        .with_eras(vec![
+            "Ancient".to_string(),
+            "Medieval".to_string(),
+            "Modern".to_string(),
+        ]);
+        let mut renderer = MockRender::default();
+        let mut tree_renderer = TreeRenderer::new(5).with_eras(true);
+
+        tree_renderer.render(&mut renderer, &tree, None).unwrap();
+
+        assert_eq!(
+            renderer.eras,
+            vec![
+                ("Ancient".to_string(), 0, 3, 50, 24),
+                ("Medieval".to_string(), 0, 33, 50, 74),
+            ]
+        );
+        assert_eq!(
+            renderer.categories,
+            HashMap::from([("bb".to_string(), "Military".to_string())])
+        );
+        assert_eq!(
+            renderer.descriptions,
+            HashMap::from([("bb".to_string(), "Unlocks swords".to_string())])
+        );
+    }
+
+    /// Puts all technologies into a single row in reverse order.
+    struct ReverseLayout;
+
+    impl Layout for ReverseLayout {
+        fn calculate_grid(&self, tree: &TechnologyTree, sizes: &[(u32, u32)], _: u32) -> Grid {
+            let cells = tree
+                .technologies()
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, technology)| {
+                    let (width, height) = sizes[technology.id().id()];
+                    GridCell::new(
+                        *technology.id(),
+                        i as u32 * 100 + 50,
+                        50,
+                        width / 2,
+                        height / 2,
+                    )
+                })
+                .collect();
+
+            Grid::new(tree.technologies().len() as u32 * 100, 100, cells)
+        }
+    }
+
+    #[test]
+    fn test_render_with_custom_layout() {
+        let tree = init_tree();
+        let mut renderer = MockRender::default();
+        let mut tree_renderer = TreeRenderer::new(5).with_layout(ReverseLayout);
+
+        tree_renderer.render(&mut renderer, &tree, None).unwrap();
+
+        assert_eq!(renderer.width, 300);
+        assert_eq!(
+            renderer.technologies,
+            HashMap::from([
+                ("a".to_string(), (250, 50)),
+                ("bb".to_string(), (150, 50)),
+                ("ccc".to_string(), (50, 50)),
+            ])
+        );
     }
 
     fn init_tree() -> TechnologyTree {
@@ -1,24 +1,98 @@
+use crate::model::technology::prerequisite::Prerequisite;
 use crate::model::technology::tree::TechnologyTree;
-use crate::model::technology::TechnologyId;
-use std::collections::VecDeque;
+use crate::model::technology::{Technology, TechnologyId};
 
-/// Calculates the depth, which is the length of the longest chain of predecessors, for each technology.
+/// Calculates the depth, which is the earliest step a technology can be researched at, for each technology.
+/// Without OR-groups, this is the length of the longest chain of predecessors.
+/// Technologies, that can never be researched because of a cycle, have a depth of 0.
 pub fn calculate_depth(tree: &TechnologyTree) -> Vec<u32> {
-    let mut numbers = vec![0; tree.technologies().len()];
-    let mut queue = VecDeque::from(calculate_technologies_without_predecessors(tree));
+    calculate_depth_with_minimum(tree, &vec![0; tree.technologies().len()])
+}
+
+/// Calculates the depth like [`calculate_depth`], but the technologies of each era start below
+/// all technologies of earlier eras, so that 2 eras never share a depth.
+/// This isn't guaranteed, if a technology without an era links a later era to an earlier one.
+pub fn calculate_depth_by_era(tree: &TechnologyTree) -> Vec<u32> {
+    let mut minimum_list = vec![0; tree.technologies().len()];
+    let mut depth_list = calculate_depth_with_minimum(tree, &minimum_list);
 
-    while let Some(id) = queue.remove(0) {
-        if let Some(technology) = tree.get(id) {
-            let next: u32 = numbers[id.id()] + 1u32;
+    for era in 1..tree.eras().len() {
+        let start = tree
+            .technologies()
+            .iter()
+            .filter(|technology| {
+                tree.get_era_index(technology)
+                    .is_some_and(|other| other < era)
+            })
+            .map(|technology| depth_list[technology.id().id()] + 1)
+            .max()
+            .unwrap_or_default();
 
-            for successor in technology.successors() {
-                numbers[successor.id()] = next.max(numbers[successor.id()]);
-                queue.push_back(*successor);
+        for technology in tree.technologies() {
+            if tree.get_era_index(technology) == Some(era) {
+                minimum_list[technology.id().id()] = start;
             }
         }
+
+        depth_list = calculate_depth_with_minimum(tree, &minimum_list);
+    }
+
+    depth_list
+}
+
+/// Calculates the row of each technology, which is its pinned row or its [depth by era](calculate_depth_by_era).
+pub fn calculate_pinned_depth(tree: &TechnologyTree) -> Vec<u32> {
+    let mut depth_list = calculate_depth_by_era(tree);
+
+    for technology in tree.technologies() {
+        if let Some(row) = technology.pin().row() {
+            depth_list[technology.id().id()] = row;
+        }
+    }
+
+    depth_list
+}
+
+/// Each technology has at least the minimum depth of the same index.
+fn calculate_depth_with_minimum(tree: &TechnologyTree, minimum_list: &[u32]) -> Vec<u32> {
+    let mut depth_list = vec![None; tree.technologies().len()];
+
+    // Similar to Dijkstra's algorithm, the technology with the lowest depth is finished next.
+    while let Some((id, depth)) = tree
+        .technologies()
+        .iter()
+        .filter(|technology| depth_list[technology.id().id()].is_none())
+        .filter_map(|technology| {
+            calculate_depth_of_technology(technology, &depth_list).map(|depth| {
+                (
+                    *technology.id(),
+                    depth.max(minimum_list[technology.id().id()]),
+                )
+            })
+        })
+        .min_by_key(|(_id, depth)| *depth)
+    {
+        depth_list[id.id()] = Some(depth);
+    }
+
+    depth_list
+        .into_iter()
+        .map(Option::unwrap_or_default)
+        .collect()
+}
+
+fn calculate_depth_of_technology(
+    technology: &Technology,
+    depth_list: &[Option<u32>],
+) -> Option<u32> {
+    if technology.predecessors().is_empty() {
+        return Some(0);
     }
 
-    numbers
+    technology
+        .prerequisite()
+        .evaluate(&|id| depth_list[id.id()])
+        .map(|depth| depth + 1)
 }
 
 pub fn group_by_depth(depth: &[u32]) -> Vec<Vec<TechnologyId>> {
@@ -40,6 +114,83 @@ pub fn calculate_technologies_without_predecessors(tree: &TechnologyTree) -> Vec
         .collect()
 }
 
+/// Calculates for each technology the cost to research it, including the costs of all its transitive predecessors.
+/// The totals saturate at [`u32::MAX`].
+/// Technologies, that can never be researched because of a cycle, have a total cost of 0.
+///
+/// Each OR-group includes its cheapest alternative on its own. This is only an approximation,
+/// because a more expensive alternative sharing predecessors with the rest of the prerequisite
+/// can lead to a lower total.
+pub fn calculate_total_costs(tree: &TechnologyTree) -> Vec<u32> {
+    calculate_cheapest_sets(tree)
+        .iter()
+        .map(|set| {
+            set.as_ref()
+                .map(|set| sum_costs(tree, set))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Calculates for each technology the cheapest set of technologies needed to research it, including itself.
+fn calculate_cheapest_sets(tree: &TechnologyTree) -> Vec<Option<Vec<bool>>> {
+    let mut sets = vec![None; tree.technologies().len()];
+
+    // Similar to calculate_depth, the technology with the lowest total cost is finished next.
+    while let Some((id, set, _cost)) = tree
+        .technologies()
+        .iter()
+        .filter(|technology| sets[technology.id().id()].is_none())
+        .filter_map(|technology| {
+            let mut set = collect_cheapest_set(tree, technology.prerequisite(), &sets)?;
+            set[technology.id().id()] = true;
+            let cost = sum_costs(tree, &set);
+            Some((*technology.id(), set, cost))
+        })
+        .min_by_key(|(_id, _set, cost)| *cost)
+    {
+        sets[id.id()] = Some(set);
+    }
+
+    sets
+}
+
+fn collect_cheapest_set(
+    tree: &TechnologyTree,
+    prerequisite: &Prerequisite<TechnologyId>,
+    sets: &[Option<Vec<bool>>],
+) -> Option<Vec<bool>> {
+    match prerequisite {
+        Prerequisite::Technology(id) => sets.get(id.id())?.clone(),
+        Prerequisite::All(children) => {
+            children
+                .iter()
+                .try_fold(vec![false; sets.len()], |mut result, child| {
+                    let child_set = collect_cheapest_set(tree, child, sets)?;
+
+                    for (value, child_value) in result.iter_mut().zip(child_set) {
+                        *value |= child_value;
+                    }
+
+                    Some(result)
+                })
+        }
+        Prerequisite::Any(children) => children
+            .iter()
+            .filter_map(|child| collect_cheapest_set(tree, child, sets))
+            .min_by_key(|set| sum_costs(tree, set)),
+    }
+}
+
+fn sum_costs(tree: &TechnologyTree, set: &[bool]) -> u32 {
+    tree.technologies()
+        .iter()
+        .filter(|technology| set[technology.id().id()])
+        .fold(0, |total: u32, technology| {
+            total.saturating_add(technology.cost())
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,10 +202,53 @@ mod tests {
         assert_eq!(calculate_depth(&init_tree()), vec![0, 0, 1, 2, 3]);
     }
 
+    #[test]
+    fn test_calculate_depth_with_any() {
+        let tree = create_tree(vec![
+            Input::test("t0", vec![]),
+            Input::test("t1", vec!["t0"]),
+            Input::test("t2", vec!["t1"]),
+            Input::with_prerequisite("t3", Prerequisite::any(vec!["t2", "t0"])),
+            Input::with_prerequisite(
+                "t4",
+                Prerequisite::Any(vec![
+                    Prerequisite::all(vec!["t0", "t2"]),
+                    Prerequisite::Technology("t5"),
+                ]),
+            ),
+            Input::test("t5", vec!["t4"]),
+        ])
+        .unwrap();
+
+        assert_eq!(calculate_depth(&tree), vec![0, 1, 2, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_calculate_depth_by_era() {
+        let era = |era: &str| Some(era.to_string());
+        let tree = create_tree(vec![
+            Input::test("t0", vec![]).with_era(era("Ancient")),
+            Input::test("t1", vec!["t0"]).with_era(era("Ancient")),
+            Input::test("t2", vec![]).with_era(era("Medieval")),
+            Input::test("t3", vec!["t2"]).with_era(era("Medieval")),
+            Input::test("t4", vec!["t0"]).with_era(era("Modern")),
+            Input::test("t5", vec!["t0"]),
+        ])
+        .unwrap()
+        .with_eras(vec![
+            "Ancient".to_string(),
+            "Medieval".to_string(),
+            "Modern".to_string(),
+        ]);
+
+        assert_eq!(calculate_depth(&tree), vec![0, 1, 0, 1, 1, 1]);
+        assert_eq!(calculate_depth_by_era(&tree), vec![0, 1, 2, 3, 4, 1]);
+    }
+
     #[test]
     fn test_group_by_depth() {
         assert_eq!(
-            group_by_depth(&vec![0, 2, 2, 0, 2, 4]),
+            group_by_depth(&[0, 2, 2, 0, 2, 4]),
             vec![
                 vec![TechnologyId::new(0), TechnologyId::new(3)],
                 vec![],
@@ -77,6 +271,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_calculate_total_costs() {
+        let tree = create_tree(vec![
+            Input::with_cost("t0", vec![], 1),
+            Input::with_cost("t1", vec![], 10),
+            Input::with_cost("t2", vec!["t0"], 100),
+            Input::with_cost("t3", vec!["t2", "t0", "t1"], 1000),
+        ])
+        .unwrap();
+
+        assert_eq!(calculate_total_costs(&tree), vec![1, 10, 101, 1111]);
+    }
+
+    #[test]
+    fn test_calculate_total_costs_with_any() {
+        let tree = create_tree(vec![
+            Input::with_cost("a", vec![], 10),
+            Input::with_cost("b", vec![], 1),
+            Input::with_cost("c", vec!["a"], 100),
+            Input::with_prerequisite("d", Prerequisite::any(vec!["a", "b"])),
+            Input::with_prerequisite(
+                "e",
+                Prerequisite::Any(vec![
+                    Prerequisite::all(vec!["b", "c"]),
+                    Prerequisite::Technology("a"),
+                ]),
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(calculate_total_costs(&tree), vec![10, 1, 110, 1, 10]);
+    }
+
+    #[test]
+    fn test_calculate_total_costs_with_shared_predecessor() {
+        let tree = create_tree(vec![
+            Input::with_cost("s", vec![], 5),
+            Input::with_cost("a", vec!["s"], 1),
+            Input::with_cost("b", vec![], 3),
+            Input::with_prerequisite(
+                "t",
+                Prerequisite::All(vec![
+                    Prerequisite::Technology("s"),
+                    Prerequisite::any(vec!["a", "b"]),
+                ]),
+            ),
+        ])
+        .unwrap();
+
+        // Researching s & a would only cost 6, but the OR-group picks b on its own
+        assert_eq!(calculate_total_costs(&tree)[3], 8);
+    }
+
+    #[test]
+    fn test_calculate_total_costs_saturate() {
+        let tree = create_tree(vec![
+            Input::with_cost("t0", vec![], u32::MAX),
+            Input::with_cost("t1", vec!["t0"], 1),
+        ])
+        .unwrap();
+
+        assert_eq!(calculate_total_costs(&tree), vec![u32::MAX, u32::MAX]);
+    }
+
     fn init_tree() -> TechnologyTree {
         create_tree(vec![
             Input::test("t0", vec![]),
@@ -1,30 +1,81 @@
 use crate::model::error::AddError;
 use crate::model::technology::name::TechnologyName;
+use crate::model::technology::prerequisite::Prerequisite;
 use crate::model::technology::tree::TechnologyTree;
 use crate::model::technology::{Input, Technology, TechnologyId};
+use crate::usecase::ranks::{chain_ranks, find_missing_ranks};
+use crate::usecase::validation::pins::validate_pinned_rows;
 use itertools::izip;
 use std::collections::HashMap;
 
 pub fn create_tree(input_list: Vec<Input>) -> Result<TechnologyTree, AddError> {
+    create(input_list, false)
+}
+
+/// Creates a tree, where each rank of a ranked technology needs the previous rank.
+/// E.g. `Armor 3` needs `Armor 2`, which needs `Armor 1`.
+pub fn create_tree_with_rank_chaining(input_list: Vec<Input>) -> Result<TechnologyTree, AddError> {
+    create(input_list, true)
+}
+
+fn create(input_list: Vec<Input>, is_chaining_ranks: bool) -> Result<TechnologyTree, AddError> {
     let name_to_id = create_name_to_id_map(&input_list)?;
-    let predecessors_list = process_predecessors(&input_list, &name_to_id)?;
-    let successors_list = process_successors(&predecessors_list);
+    let prerequisite_list = process_prerequisites(&input_list, &name_to_id)?;
+    let names = input_list
+        .iter()
+        .map(|input| TechnologyName::new(input.name()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if is_chaining_ranks {
+        if let Some(diagnostic) = find_missing_ranks(&names).into_iter().next() {
+            return Err(diagnostic.into_error());
+        }
+    }
+
+    let tree = build_tree(input_list, names, prerequisite_list, is_chaining_ranks);
+    validate_pinned_rows(&tree)?;
+
+    Ok(tree)
+}
+
+/// Builds the tree from already validated parts.
+pub(crate) fn build_tree(
+    input_list: Vec<Input>,
+    names: Vec<TechnologyName>,
+    mut prerequisite_list: Vec<Prerequisite<TechnologyId>>,
+    is_chaining_ranks: bool,
+) -> TechnologyTree {
+    if is_chaining_ranks {
+        chain_ranks(&names, &mut prerequisite_list);
+    }
+
+    let successors_list = process_successors(&prerequisite_list);
     let mut technologies = Vec::new();
 
-    for (input, predecessors, successors) in izip!(input_list, predecessors_list, successors_list) {
+    for (input, name, prerequisite, successors) in
+        izip!(input_list, names, prerequisite_list, successors_list)
+    {
         let technology = Technology::new(
             TechnologyId::new(technologies.len()),
-            TechnologyName::new(input.name())?,
-            predecessors,
+            name,
+            prerequisite,
             successors,
-        );
+            input.cost(),
+        )
+        .with_pin(*input.pin())
+        .with_era(input.era().map(str::to_string))
+        .with_category(input.category().map(str::to_string))
+        .with_description(input.description().map(str::to_string))
+        .with_icon(input.icon().map(str::to_string));
         technologies.push(technology);
     }
 
-    Ok(TechnologyTree::new(technologies))
+    TechnologyTree::new(technologies)
 }
 
-fn create_name_to_id_map(technologies: &[Input]) -> Result<HashMap<String, usize>, AddError> {
+pub(crate) fn create_name_to_id_map(
+    technologies: &[Input],
+) -> Result<HashMap<String, usize>, AddError> {
     let mut name_to_id = HashMap::new();
 
     for technology in technologies {
@@ -39,27 +90,31 @@ fn create_name_to_id_map(technologies: &[Input]) -> Result<HashMap<String, usize
     Ok(name_to_id)
 }
 
-fn process_predecessors(
+pub(crate) fn process_prerequisites(
     technologies: &[Input],
     name_to_id: &HashMap<String, usize>,
-) -> Result<Vec<Vec<TechnologyId>>, AddError> {
+) -> Result<Vec<Prerequisite<TechnologyId>>, AddError> {
     technologies
         .iter()
         .map(|technology| {
+            if technology.prerequisite().has_empty_group() {
+                return Err(AddError::EmptyGroup(technology.name().to_string()));
+            }
+
             technology
-                .predecessors()
-                .iter()
-                .map(|name| into_id(name, name_to_id))
-                .collect()
+                .prerequisite()
+                .try_map(&|name| into_id(name, name_to_id))
         })
         .collect()
 }
 
-fn process_successors(predecessors_list: &[Vec<TechnologyId>]) -> Vec<Vec<TechnologyId>> {
-    let mut successors: Vec<Vec<TechnologyId>> = vec![Vec::new(); predecessors_list.len()];
+pub(crate) fn process_successors(
+    prerequisite_list: &[Prerequisite<TechnologyId>],
+) -> Vec<Vec<TechnologyId>> {
+    let mut successors: Vec<Vec<TechnologyId>> = vec![Vec::new(); prerequisite_list.len()];
 
-    for (id, predecessors) in predecessors_list.iter().enumerate() {
-        for predecessor in predecessors {
+    for (id, prerequisite) in prerequisite_list.iter().enumerate() {
+        for predecessor in prerequisite.get_technologies() {
             successors
                 .get_mut(predecessor.id())
                 .unwrap()
@@ -80,6 +135,7 @@ fn into_id(name: &str, name_to_id: &HashMap<String, usize>) -> Result<Technology
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::technology::pin::Pin;
 
     #[test]
     fn test_create_tree() {
@@ -103,6 +159,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_tree_with_invalid_row() {
+        let input = vec![
+            Input::test("t0", vec![]),
+            Input::test("t1", vec![]).with_pin(Pin::new(Some(u32::MAX), None)),
+        ];
+
+        assert_eq!(
+            create_tree(input),
+            Err(AddError::InvalidRow("t1".to_string(), u32::MAX, 2))
+        );
+    }
+
+    #[test]
+    fn test_create_tree_with_any() {
+        let prerequisite = Prerequisite::Any(vec![
+            Prerequisite::all(vec!["t0", "t1"]),
+            Prerequisite::Technology("t2"),
+        ]);
+        let input = vec![
+            Input::test("t0", vec![]),
+            Input::test("t1", vec![]),
+            Input::test("t2", vec![]),
+            Input::with_prerequisite("t3", prerequisite.clone()),
+        ];
+
+        let tree = create_tree(input).unwrap();
+        let technology = tree.get(TechnologyId::new(3)).unwrap();
+
+        assert_eq!(
+            prerequisite.try_map(&|name| tree.find(name).map(|t| *t.id()).ok_or(*name)),
+            Ok(technology.prerequisite().clone())
+        );
+        assert_eq!(
+            technology.predecessors(),
+            &vec![
+                TechnologyId::new(0),
+                TechnologyId::new(1),
+                TechnologyId::new(2)
+            ]
+        );
+        assert_eq!(
+            tree.get(TechnologyId::new(2)).unwrap().successors(),
+            &vec![TechnologyId::new(3)]
+        );
+    }
+
     #[test]
     fn test_wrong_order() {
         let input = vec![Input::test("t0", vec!["t1"]), Input::test("t1", vec![])];
@@ -127,6 +230,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_empty_group() {
+        let input = vec![Input::with_prerequisite("t0", Prerequisite::Any(vec![]))];
+
+        assert_eq!(
+            create_tree(input),
+            Err(AddError::EmptyGroup("t0".to_string()))
+        );
+    }
+
     #[test]
     fn test_duplicate_name() {
         let name = "duplicate";
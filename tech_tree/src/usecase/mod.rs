@@ -1,3 +1,4 @@
 pub mod analysis;
 pub mod creation;
+pub mod ranks;
 pub mod validation;
@@ -0,0 +1,344 @@
+use crate::model::error::{AddError, Diagnostic};
+use crate::model::technology::name::TechnologyName;
+use crate::model::technology::prerequisite::Prerequisite;
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::{Technology, TechnologyId};
+use crate::usecase::creation::process_successors;
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+/// Adds the previous rank of each ranked technology as an implicit predecessor.
+/// Missing ranks are skipped, so they should be checked with [`find_missing_ranks`] first.
+pub fn chain_ranks(names: &[TechnologyName], prerequisite_list: &mut [Prerequisite<TechnologyId>]) {
+    for series in group_by_base(names).into_values() {
+        for ((_, previous_id), (_, id)) in series.into_iter().tuple_windows() {
+            let prerequisite = std::mem::take(&mut prerequisite_list[id.id()]);
+            prerequisite_list[id.id()] = prerequisite.and_technology(previous_id);
+        }
+    }
+}
+
+/// Finds all ranks between the lowest & the highest rank of each series, that are missing.
+/// Each error belongs to the technology after the gap.
+pub fn find_missing_ranks(names: &[TechnologyName]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for (base, series) in group_by_base(names) {
+        for ((previous_rank, _), (rank, id)) in series.into_iter().tuple_windows() {
+            for missing_rank in (previous_rank + 1)..rank {
+                diagnostics.push(Diagnostic::new(
+                    AddError::MissingRank(format!("{} {}", base, missing_rank)),
+                    Some(id.id()),
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Returns a tree, where each run of contiguous ranks is combined into a single technology.
+/// E.g. `Armor 1` to `Armor 5` become `Armor 1–5`, but `Armor 1` & `Armor 3` stay separate.
+/// The second result contains the original technologies of each new one.
+/// Fails for predecessors, that are not part of the tree.
+pub fn collapse_ranks(
+    tree: &TechnologyTree,
+) -> Result<(TechnologyTree, Vec<Vec<TechnologyId>>), AddError> {
+    let names: Vec<TechnologyName> = tree
+        .technologies()
+        .iter()
+        .map(|technology| technology.name().clone())
+        .collect();
+    let runs: Vec<Vec<TechnologyId>> = group_by_base(&names)
+        .into_values()
+        .flat_map(split_into_runs)
+        .collect();
+    let mut members_list: Vec<Vec<TechnologyId>> = Vec::new();
+    let mut old_to_new: Vec<Option<TechnologyId>> = vec![None; names.len()];
+
+    for technology in tree.technologies() {
+        if old_to_new[technology.id().id()].is_some() {
+            continue;
+        }
+
+        let members = runs
+            .iter()
+            .find(|run| run.contains(technology.id()))
+            .cloned()
+            .unwrap_or_else(|| vec![*technology.id()]);
+        let new_id = TechnologyId::new(members_list.len());
+
+        for id in &members {
+            old_to_new[id.id()] = Some(new_id);
+        }
+
+        members_list.push(members);
+    }
+
+    let prerequisite_list: Vec<Prerequisite<TechnologyId>> = members_list
+        .iter()
+        .enumerate()
+        .map(|(new_id, members)| {
+            combine_prerequisites(tree, members, &old_to_new, TechnologyId::new(new_id))
+        })
+        .collect::<Result<_, _>>()?;
+    let successors_list = process_successors(&prerequisite_list);
+
+    let technologies = members_list
+        .iter()
+        .zip(prerequisite_list)
+        .zip(successors_list)
+        .enumerate()
+        .map(|(new_id, ((members, prerequisite), successors))| {
+            let first = tree.get(members[0]).unwrap();
+
+            Technology::new(
+                TechnologyId::new(new_id),
+                get_collapsed_name(tree, members),
+                prerequisite,
+                successors,
+                members
+                    .iter()
+                    .filter_map(|id| tree.get(*id))
+                    .map(|technology| technology.cost())
+                    .sum(),
+            )
+            .with_pin(*first.pin())
+            .with_era(first.era().map(str::to_string))
+            .with_category(first.category().map(str::to_string))
+            .with_description(first.description().map(str::to_string))
+            .with_icon(first.icon().map(str::to_string))
+        })
+        .collect();
+
+    Ok((
+        TechnologyTree::new(technologies).with_eras(tree.eras().clone()),
+        members_list,
+    ))
+}
+
+/// Groups the ranked technologies by their base name and sorts them by rank.
+fn group_by_base(names: &[TechnologyName]) -> BTreeMap<String, Vec<(u8, TechnologyId)>> {
+    let mut groups: BTreeMap<String, Vec<(u8, TechnologyId)>> = BTreeMap::new();
+
+    for (id, name) in names.iter().enumerate() {
+        if let Some(rank) = name.get_rank() {
+            groups
+                .entry(name.get_base().to_string())
+                .or_default()
+                .push((rank, TechnologyId::new(id)));
+        }
+    }
+
+    for series in groups.values_mut() {
+        series.sort_by_key(|(rank, _id)| *rank);
+    }
+
+    groups
+}
+
+/// Splits a series sorted by rank at each missing rank.
+fn split_into_runs(series: Vec<(u8, TechnologyId)>) -> Vec<Vec<TechnologyId>> {
+    let mut runs: Vec<Vec<TechnologyId>> = Vec::new();
+    let mut previous_rank = None;
+
+    for (rank, id) in series {
+        match runs.last_mut() {
+            Some(run) if previous_rank.map(|previous| previous + 1) == Some(rank) => run.push(id),
+            _ => runs.push(vec![id]),
+        }
+
+        previous_rank = Some(rank);
+    }
+
+    runs
+}
+
+fn get_collapsed_name(tree: &TechnologyTree, members: &[TechnologyId]) -> TechnologyName {
+    let first = tree.get(members[0]).unwrap().name();
+
+    if members.len() == 1 {
+        return first.clone();
+    }
+
+    let last = tree.get(members[members.len() - 1]).unwrap().name();
+
+    TechnologyName::Simple(format!(
+        "{} {}–{}",
+        first.get_base(),
+        first.get_rank().unwrap_or_default(),
+        last.get_rank().unwrap_or_default()
+    ))
+}
+
+/// Combines the prerequisites of all members, but ignores links between them.
+fn combine_prerequisites(
+    tree: &TechnologyTree,
+    members: &[TechnologyId],
+    old_to_new: &[Option<TechnologyId>],
+    new_id: TechnologyId,
+) -> Result<Prerequisite<TechnologyId>, AddError> {
+    let mut children = Vec::new();
+
+    for id in members {
+        let prerequisite = tree
+            .get(*id)
+            .unwrap()
+            .prerequisite()
+            .try_map(&|id| {
+                old_to_new
+                    .get(id.id())
+                    .copied()
+                    .flatten()
+                    .ok_or(AddError::UnknownTechnology(id.id()))
+            })?
+            .remove(&new_id);
+
+        let new_children = match prerequisite {
+            Some(Prerequisite::All(children)) => children,
+            Some(prerequisite) => vec![prerequisite],
+            None => vec![],
+        };
+
+        for child in new_children {
+            if !children.contains(&child) {
+                children.push(child);
+            }
+        }
+    }
+
+    Ok(Prerequisite::All(children))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::{create_tree, create_tree_with_rank_chaining};
+
+    #[test]
+    fn test_chain_ranks() {
+        let tree = create_tree_with_rank_chaining(vec![
+            Input::test("Armor 2", vec!["Metal"]),
+            Input::test("Metal", vec![]),
+            Input::test("Armor 1", vec![]),
+            Input::test("Armor 3", vec!["Armor 2"]),
+        ])
+        .unwrap();
+
+        assert_predecessors(&tree, 0, vec![2, 1]);
+        assert_predecessors(&tree, 1, vec![]);
+        assert_predecessors(&tree, 2, vec![]);
+        assert_predecessors(&tree, 3, vec![0]);
+    }
+
+    #[test]
+    fn test_chain_ranks_with_missing_rank() {
+        assert_eq!(
+            create_tree_with_rank_chaining(vec![
+                Input::test("Armor 1", vec![]),
+                Input::test("Armor 3", vec![]),
+            ]),
+            Err(AddError::MissingRank("Armor 2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_find_missing_ranks() {
+        let names: Vec<TechnologyName> = vec!["A 1", "B 1", "A 4", "B 3", "C"]
+            .into_iter()
+            .map(|name| TechnologyName::new(name).unwrap())
+            .collect();
+
+        assert_eq!(
+            find_missing_ranks(&names),
+            vec![
+                Diagnostic::new(AddError::MissingRank("A 2".to_string()), Some(2)),
+                Diagnostic::new(AddError::MissingRank("A 3".to_string()), Some(2)),
+                Diagnostic::new(AddError::MissingRank("B 2".to_string()), Some(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_without_chaining() {
+        let tree = create_tree(vec![
+            Input::test("Armor 1", vec![]),
+            Input::test("Armor 3", vec![]),
+        ])
+        .unwrap();
+
+        assert_predecessors(&tree, 1, vec![]);
+    }
+
+    #[test]
+    fn test_collapse_ranks() {
+        let tree = create_tree_with_rank_chaining(vec![
+            Input::test("Metal", vec![]),
+            Input::with_cost("Armor 1", vec!["Metal"], 10),
+            Input::with_cost("Armor 2", vec!["Alloy"], 20),
+            Input::test("Alloy", vec!["Metal"]),
+            Input::test("Tank", vec!["Armor 2"]),
+        ])
+        .unwrap();
+
+        let (collapsed, members) = collapse_ranks(&tree).unwrap();
+
+        assert_eq!(
+            members,
+            vec![ids(vec![0]), ids(vec![1, 2]), ids(vec![3]), ids(vec![4])]
+        );
+        assert_name(&collapsed, 0, "Metal");
+        assert_name(&collapsed, 1, "Armor 1–2");
+        assert_name(&collapsed, 2, "Alloy");
+        assert_name(&collapsed, 3, "Tank");
+        assert_predecessors(&collapsed, 1, vec![0, 2]);
+        assert_predecessors(&collapsed, 3, vec![1]);
+        assert_eq!(
+            collapsed.get(TechnologyId::new(0)).unwrap().successors(),
+            &ids(vec![1, 2])
+        );
+        assert_eq!(collapsed.get(TechnologyId::new(1)).unwrap().cost(), 30);
+    }
+
+    #[test]
+    fn test_collapse_ranks_with_missing_rank() {
+        let tree = create_tree(vec![
+            Input::test("Armor 1", vec![]),
+            Input::test("Armor 3", vec![]),
+            Input::test("Armor 4", vec![]),
+        ])
+        .unwrap();
+
+        let (collapsed, members) = collapse_ranks(&tree).unwrap();
+
+        assert_eq!(members, vec![ids(vec![0]), ids(vec![1, 2])]);
+        assert_name(&collapsed, 0, "Armor 1");
+        assert_name(&collapsed, 1, "Armor 3–4");
+    }
+
+    #[test]
+    fn test_collapse_ranks_with_unknown_predecessor() {
+        let tree = TechnologyTree::new(vec![Technology::simple2(0, "Armor 1", vec![3], vec![])]);
+
+        assert_eq!(collapse_ranks(&tree), Err(AddError::UnknownTechnology(3)));
+    }
+
+    fn ids(ids: Vec<usize>) -> Vec<TechnologyId> {
+        ids.into_iter().map(TechnologyId::new).collect()
+    }
+
+    fn assert_name(tree: &TechnologyTree, id: usize, name: &str) {
+        assert_eq!(
+            tree.get(TechnologyId::new(id)).unwrap().name().get_full(),
+            name
+        );
+    }
+
+    fn assert_predecessors(tree: &TechnologyTree, id: usize, predecessors: Vec<usize>) {
+        assert_eq!(
+            tree.get(TechnologyId::new(id)).unwrap().predecessors(),
+            &ids(predecessors)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::model::error::AddError;
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::TechnologyId;
+
+pub fn validate_eras(tree: TechnologyTree) -> Result<TechnologyTree, AddError> {
+    if let Some((_id, error)) = find_unknown_eras(&tree)
+        .into_iter()
+        .chain(find_predecessors_in_later_eras(&tree))
+        .next()
+    {
+        return Err(error);
+    }
+
+    Ok(tree)
+}
+
+/// Finds technologies, whose era isn't one of the [eras of the tree](TechnologyTree::eras).
+pub fn find_unknown_eras(tree: &TechnologyTree) -> Vec<(TechnologyId, AddError)> {
+    tree.technologies()
+        .iter()
+        .filter_map(|technology| {
+            let era = technology.era()?;
+
+            if tree.get_era_index(technology).is_some() {
+                return None;
+            }
+
+            Some((
+                *technology.id(),
+                AddError::UnknownEra(technology.name().get_full().to_string(), era.to_string()),
+            ))
+        })
+        .collect()
+}
+
+/// Finds predecessors, that belong to a later era than their successor.
+/// Technologies without a known era are ignored.
+pub fn find_predecessors_in_later_eras(tree: &TechnologyTree) -> Vec<(TechnologyId, AddError)> {
+    let mut errors = Vec::new();
+
+    for technology in tree.technologies() {
+        let era = match tree.get_era_index(technology) {
+            Some(era) => era,
+            None => continue,
+        };
+
+        for id in technology.predecessors() {
+            let predecessor = tree.get(*id).unwrap();
+
+            if tree
+                .get_era_index(predecessor)
+                .is_some_and(|other| other > era)
+            {
+                errors.push((
+                    *technology.id(),
+                    AddError::PredecessorInLaterEra(
+                        technology.name().get_full().to_string(),
+                        predecessor.name().get_full().to_string(),
+                    ),
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    fn input(name: &str, predecessors: Vec<&str>, era: Option<&str>) -> Input {
+        Input::test(name, predecessors).with_era(era.map(str::to_string))
+    }
+
+    fn eras() -> Vec<String> {
+        vec!["Ancient".to_string(), "Medieval".to_string()]
+    }
+
+    #[test]
+    fn test_valid_eras() {
+        let tree = create_tree(vec![
+            input("a", vec![], Some("Ancient")),
+            input("b", vec!["a"], Some("Medieval")),
+            input("c", vec!["a", "b"], Some("Medieval")),
+            input("d", vec!["c"], None),
+        ])
+        .unwrap()
+        .with_eras(eras());
+
+        assert_eq!(validate_eras(tree.clone()), Ok(tree));
+    }
+
+    #[test]
+    fn test_predecessor_in_later_era() {
+        let tree = create_tree(vec![
+            input("a", vec![], Some("Ancient")),
+            input("b", vec![], Some("Medieval")),
+            input("c", vec!["a", "b"], Some("Ancient")),
+            input("d", vec!["b"], None),
+        ])
+        .unwrap()
+        .with_eras(eras());
+
+        assert_eq!(
+            find_predecessors_in_later_eras(&tree),
+            vec![(
+                TechnologyId::new(2),
+                AddError::PredecessorInLaterEra("c".to_string(), "b".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_unknown_era() {
+        let tree = create_tree(vec![
+            input("a", vec![], Some("Ancient")),
+            input("b", vec!["a"], Some("Modern")),
+        ])
+        .unwrap()
+        .with_eras(eras());
+
+        assert_eq!(
+            validate_eras(tree),
+            Err(AddError::UnknownEra("b".to_string(), "Modern".to_string()))
+        );
+    }
+}
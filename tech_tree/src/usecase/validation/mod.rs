@@ -1 +1,5 @@
+pub mod eras;
 pub mod no_cycles;
+pub mod pins;
+pub mod pipeline;
+pub mod redundancy;
@@ -10,9 +10,12 @@ pub fn validate_no_cycles(tree: TechnologyTree) -> Result<TechnologyTree, AddErr
     Ok(tree)
 }
 
+/// Only cycles, which prevent technologies from being researched, are errors.
+/// A cycle through an OR-group is fine, if another alternative of the group can be researched.
 fn validate_tree(tree: &TechnologyTree) -> Option<Vec<String>> {
     let len = tree.technologies().len();
-    let mut visited = vec![false; len];
+    // Technologies, that can be researched, are handled as visited.
+    let mut visited = calculate_researchable(tree);
     let mut recursive = vec![false; len];
 
     for technology in tree.technologies() {
@@ -26,6 +29,58 @@ fn validate_tree(tree: &TechnologyTree) -> Option<Vec<String>> {
     None
 }
 
+/// Finds all cycles instead of only the first one.
+/// Technologies of a found cycle are not part of other cycles.
+pub fn find_cycles(tree: &TechnologyTree) -> Vec<Vec<String>> {
+    let len = tree.technologies().len();
+    let mut visited = calculate_researchable(tree);
+    let mut recursive = vec![false; len];
+    let mut cycles = Vec::new();
+
+    for technology in tree.technologies() {
+        while let Some(circle) =
+            validate_technology(tree, *technology.id(), &mut visited, &mut recursive)
+        {
+            // Only the technologies of the circle stay visited. The rest of the aborted search is repeated.
+            for (is_visited, is_recursive) in visited.iter_mut().zip(recursive.iter_mut()) {
+                if *is_recursive {
+                    *is_visited = false;
+                    *is_recursive = false;
+                }
+            }
+
+            cycles.push(circle);
+        }
+    }
+
+    cycles
+}
+
+/// Calculates which technologies can be researched, if all technologies are researched as early as possible.
+fn calculate_researchable(tree: &TechnologyTree) -> Vec<bool> {
+    let mut researchable = vec![false; tree.technologies().len()];
+    let mut has_changed = true;
+
+    while has_changed {
+        has_changed = false;
+
+        for technology in tree.technologies() {
+            let i = technology.id().id();
+
+            if !researchable[i]
+                && technology
+                    .prerequisite()
+                    .is_satisfied(&|id| researchable[id.id()])
+            {
+                researchable[i] = true;
+                has_changed = true;
+            }
+        }
+    }
+
+    researchable
+}
+
 fn validate_technology(
     tree: &TechnologyTree,
     id: TechnologyId,
@@ -79,6 +134,7 @@ fn get_circle(tree: &TechnologyTree, id: TechnologyId, recursive: &mut [bool]) -
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::technology::prerequisite::Prerequisite;
     use crate::model::technology::Input;
     use crate::usecase::creation::create_tree;
 
@@ -96,6 +152,58 @@ mod tests {
         assert_eq!(validate_tree(&tree), None);
     }
 
+    #[test]
+    fn test_cycle_through_any() {
+        let tree = create_tree(vec![
+            Input::test("t0", vec![]),
+            Input::with_prerequisite("t1", Prerequisite::any(vec!["t0", "t2"])),
+            Input::test("t2", vec!["t1"]),
+        ])
+        .unwrap();
+
+        assert_eq!(validate_tree(&tree), None);
+    }
+
+    #[test]
+    fn test_cycle_through_any_without_alternative() {
+        let tree = create_tree(vec![
+            Input::test("t0", vec!["t2"]),
+            Input::with_prerequisite("t1", Prerequisite::any(vec!["t0", "t2"])),
+            Input::test("t2", vec!["t1"]),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            validate_tree(&tree),
+            Some(vec!["t0".to_string(), "t1".to_string(), "t2".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let tree = create_tree(vec![
+            Input::test("t0", vec!["t1"]),
+            Input::test("t1", vec!["t0"]),
+            Input::test("t2", vec!["t0", "t4"]),
+            Input::test("t3", vec!["t2"]),
+            Input::test("t4", vec!["t3"]),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            find_cycles(&tree),
+            vec![
+                vec!["t0".to_string(), "t1".to_string()],
+                vec!["t2".to_string(), "t3".to_string(), "t4".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_no_cycles() {
+        assert!(find_cycles(&init_tree(vec![])).is_empty());
+    }
+
     #[test]
     fn test_error() {
         let tree = init_tree(vec!["t3"]);
@@ -0,0 +1,169 @@
+use crate::model::error::AddError;
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::TechnologyId;
+use crate::usecase::analysis::calculate_pinned_depth;
+use std::collections::HashMap;
+
+pub fn validate_pins(tree: TechnologyTree) -> Result<TechnologyTree, AddError> {
+    if let Some((_id, error)) = find_conflicting_pins(&tree).into_iter().next() {
+        return Err(error);
+    }
+
+    Ok(tree)
+}
+
+/// Fails for the first technology pinned to a row beyond the number of technologies,
+/// which would need that many empty rows.
+pub fn validate_pinned_rows(tree: &TechnologyTree) -> Result<(), AddError> {
+    let count = tree.technologies().len();
+
+    for technology in tree.technologies() {
+        if let Some(row) = technology.pin().row() {
+            if row as usize >= count {
+                let name = technology.name().get_full().to_string();
+                return Err(AddError::InvalidRow(name, row, count));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds technologies pinned to the same column of the same row, rows beyond the number of technologies
+/// & pinned rows, which aren't below the rows of all predecessors.
+/// Technologies without a pinned row use their depth by era.
+/// Each error belongs to the later technology or the successor.
+pub fn find_conflicting_pins(tree: &TechnologyTree) -> Vec<(TechnologyId, AddError)> {
+    let rows = calculate_pinned_depth(tree);
+    let mut positions: HashMap<(u32, u32), &str> = HashMap::new();
+    let mut errors = Vec::new();
+
+    for technology in tree.technologies() {
+        let pin = technology.pin();
+        let row = rows[technology.id().id()];
+        let name = technology.name().get_full();
+
+        if let Some(pinned_row) = pin.row() {
+            if pinned_row as usize >= tree.technologies().len() {
+                errors.push((
+                    *technology.id(),
+                    AddError::InvalidRow(name.to_string(), pinned_row, tree.technologies().len()),
+                ));
+                continue;
+            }
+        }
+
+        for predecessor in technology.predecessors() {
+            let other = tree.get(*predecessor).unwrap();
+            let is_pinned = pin.row().is_some() || other.pin().row().is_some();
+
+            if is_pinned && rows[predecessor.id()] >= row {
+                errors.push((
+                    *technology.id(),
+                    AddError::PredecessorNotAbove(
+                        name.to_string(),
+                        other.name().get_full().to_string(),
+                    ),
+                ));
+            }
+        }
+
+        if let Some(column) = pin.column() {
+            if let Some(other) = positions.get(&(row, column)) {
+                errors.push((
+                    *technology.id(),
+                    AddError::ConflictingPins(other.to_string(), name.to_string()),
+                ));
+            } else {
+                positions.insert((row, column), name);
+            }
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::pin::Pin;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    #[test]
+    fn test_valid_pins() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]).with_pin(Pin::new(None, Some(0))),
+            Input::test("b", vec![]).with_pin(Pin::new(Some(1), Some(0))),
+            Input::test("c", vec![]).with_pin(Pin::new(Some(2), None)),
+        ])
+        .unwrap();
+
+        assert_eq!(validate_pins(tree.clone()), Ok(tree));
+    }
+
+    #[test]
+    fn test_conflicting_pins() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]).with_pin(Pin::new(None, Some(1))),
+            Input::test("b", vec!["a"]).with_pin(Pin::new(None, Some(1))),
+            Input::test("c", vec![]).with_pin(Pin::new(Some(1), Some(1))),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            find_conflicting_pins(&tree),
+            vec![(
+                TechnologyId::new(2),
+                AddError::ConflictingPins("b".to_string(), "c".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_invalid_row() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]),
+            Input::test("b", vec![]).with_pin(Pin::new(Some(1), None)),
+        ])
+        .unwrap();
+        let mut technologies = tree.technologies().to_vec();
+        technologies[0] = technologies[0]
+            .clone()
+            .with_pin(Pin::new(Some(u32::MAX), None));
+        let tree = TechnologyTree::new(technologies);
+        let error = || AddError::InvalidRow("a".to_string(), u32::MAX, 2);
+
+        assert_eq!(
+            find_conflicting_pins(&tree),
+            vec![(TechnologyId::new(0), error())]
+        );
+        assert_eq!(validate_pinned_rows(&tree), Err(error()));
+    }
+
+    #[test]
+    fn test_predecessor_not_above() {
+        let tree = create_tree(vec![
+            Input::test("a", vec![]).with_pin(Pin::new(Some(1), None)),
+            Input::test("b", vec!["a"]),
+            Input::test("c", vec![]),
+            Input::test("d", vec!["c"]).with_pin(Pin::new(Some(0), None)),
+            Input::test("e", vec!["c"]).with_pin(Pin::new(Some(2), None)),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            find_conflicting_pins(&tree),
+            vec![
+                (
+                    TechnologyId::new(1),
+                    AddError::PredecessorNotAbove("b".to_string(), "a".to_string())
+                ),
+                (
+                    TechnologyId::new(3),
+                    AddError::PredecessorNotAbove("d".to_string(), "c".to_string())
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use crate::model::error::{AddError, Diagnostic};
+use crate::model::technology::name::TechnologyName;
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::Input;
+use crate::usecase::creation::{build_tree, create_name_to_id_map, process_prerequisites};
+use crate::usecase::ranks::find_missing_ranks;
+use crate::usecase::validation::eras::{find_predecessors_in_later_eras, find_unknown_eras};
+use crate::usecase::validation::no_cycles::find_cycles;
+use crate::usecase::validation::pins::find_conflicting_pins;
+use std::collections::HashSet;
+
+/// Creates & validates a tree like [`create_tree`](crate::usecase::creation::create_tree) and
+/// [`validate_no_cycles`](crate::usecase::validation::no_cycles::validate_no_cycles),
+/// but collects all errors instead of failing on the first one.
+///
+/// Invalid technologies & predecessors are ignored after reporting them,
+/// so the remaining tree can still be checked for cycles.
+/// The eras are in chronological order & the era of each technology must be one of them.
+pub fn create_validated_tree(
+    input_list: Vec<Input>,
+    eras: Vec<String>,
+    is_chaining_ranks: bool,
+) -> Result<TechnologyTree, Vec<Diagnostic>> {
+    let mut diagnostics = Vec::new();
+    let all_names: HashSet<String> = input_list
+        .iter()
+        .map(|input| input.name().to_string())
+        .collect();
+    let mut valid_names = HashSet::new();
+    let mut names = Vec::new();
+    let mut valid_input_list = Vec::new();
+    // The index of each valid input in the original list
+    let mut original_indices = Vec::new();
+
+    for (index, input) in input_list.into_iter().enumerate() {
+        if valid_names.contains(input.name()) {
+            let error = AddError::NameExists(input.name().to_string());
+            diagnostics.push(Diagnostic::new(error, Some(index)));
+            continue;
+        }
+
+        match TechnologyName::new(input.name()) {
+            Ok(name) => {
+                valid_names.insert(input.name().to_string());
+                names.push(name);
+                valid_input_list.push(input);
+                original_indices.push(index);
+            }
+            Err(error) => diagnostics.push(Diagnostic::new(error, Some(index))),
+        }
+    }
+
+    for (input, index) in valid_input_list.iter_mut().zip(original_indices.iter()) {
+        let prerequisite = input.prerequisite();
+
+        if prerequisite.has_empty_group() {
+            let error = AddError::EmptyGroup(input.name().to_string());
+            diagnostics.push(Diagnostic::new(error, Some(*index)));
+        }
+
+        for predecessor in prerequisite.get_technologies() {
+            if !all_names.contains(&predecessor) {
+                let error = AddError::UnknownPredecessor(predecessor);
+                diagnostics.push(Diagnostic::new(error, Some(*index)));
+            }
+        }
+
+        let valid_prerequisite = prerequisite
+            .retain(&|predecessor| valid_names.contains(predecessor))
+            .unwrap_or_default();
+        input.set_prerequisite(valid_prerequisite);
+    }
+
+    if is_chaining_ranks {
+        diagnostics.extend(find_missing_ranks(&names).into_iter().map(|diagnostic| {
+            let index = diagnostic.index().map(|i| original_indices[i]);
+            Diagnostic::new(diagnostic.into_error(), index)
+        }));
+    }
+
+    let name_to_id = create_name_to_id_map(&valid_input_list).unwrap();
+    let prerequisite_list = process_prerequisites(&valid_input_list, &name_to_id).unwrap();
+    let tree = build_tree(
+        valid_input_list,
+        names,
+        prerequisite_list,
+        is_chaining_ranks,
+    )
+    .with_eras(eras);
+
+    for cycle in find_cycles(&tree) {
+        let index = tree
+            .find(&cycle[0])
+            .map(|technology| original_indices[technology.id().id()]);
+        diagnostics.push(Diagnostic::new(AddError::Cycle(cycle), index));
+    }
+
+    for (id, error) in find_conflicting_pins(&tree)
+        .into_iter()
+        .chain(find_unknown_eras(&tree))
+        .chain(find_predecessors_in_later_eras(&tree))
+    {
+        diagnostics.push(Diagnostic::new(error, Some(original_indices[id.id()])));
+    }
+
+    if diagnostics.is_empty() {
+        Ok(tree)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::pin::Pin;
+    use crate::model::technology::prerequisite::Prerequisite;
+    use crate::usecase::creation::create_tree;
+
+    #[test]
+    fn test_valid_tree() {
+        let input_list = vec![
+            Input::test("t0", vec![]),
+            Input::test("t1", vec!["t0"]),
+            Input::test("t2", vec!["t0", "t1"]),
+        ];
+
+        assert_eq!(
+            create_validated_tree(input_list.clone(), Vec::new(), false),
+            Ok(create_tree(input_list).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_all_errors() {
+        let input_list = vec![
+            Input::test("t0", vec!["t3"]),
+            Input::test("t0", vec![]),
+            Input::test("  ", vec![]),
+            Input::test("t1", vec!["unknown0", "t0"]),
+            Input::with_prerequisite("t2", Prerequisite::Any(vec![])),
+            Input::test("t3", vec!["t1", "unknown1"]),
+            Input::test("Armor 1", vec![]),
+            Input::test("Armor 3", vec![]),
+            Input::test("t4", vec!["t5"]),
+            Input::test("t5", vec!["t4"]),
+            Input::test("t6", vec![]).with_pin(Pin::new(Some(3), Some(0))),
+            Input::test("t7", vec![]).with_pin(Pin::new(Some(3), Some(0))),
+            Input::test("t8", vec!["t9"]).with_era(Some("Ancient".to_string())),
+            Input::test("t9", vec![]).with_era(Some("Medieval".to_string())),
+            Input::test("t10", vec![]).with_era(Some("Modern".to_string())),
+        ];
+        let eras = vec!["Ancient".to_string(), "Medieval".to_string()];
+
+        assert_eq!(
+            create_validated_tree(input_list, eras, true),
+            Err(vec![
+                diagnostic(AddError::NameExists("t0".to_string()), 1),
+                diagnostic(AddError::InvalidName("  ".to_string()), 2),
+                diagnostic(AddError::UnknownPredecessor("unknown0".to_string()), 3),
+                diagnostic(AddError::EmptyGroup("t2".to_string()), 4),
+                diagnostic(AddError::UnknownPredecessor("unknown1".to_string()), 5),
+                diagnostic(AddError::MissingRank("Armor 2".to_string()), 7),
+                diagnostic(
+                    AddError::Cycle(vec!["t0".to_string(), "t1".to_string(), "t3".to_string()]),
+                    0,
+                ),
+                diagnostic(AddError::Cycle(vec!["t4".to_string(), "t5".to_string()]), 8,),
+                diagnostic(
+                    AddError::ConflictingPins("t6".to_string(), "t7".to_string()),
+                    11
+                ),
+                diagnostic(
+                    AddError::UnknownEra("t10".to_string(), "Modern".to_string()),
+                    14
+                ),
+                diagnostic(
+                    AddError::PredecessorInLaterEra("t8".to_string(), "t9".to_string()),
+                    12
+                ),
+            ])
+        );
+    }
+
+    fn diagnostic(error: AddError, index: usize) -> Diagnostic {
+        Diagnostic::new(error, Some(index))
+    }
+}
@@ -0,0 +1,310 @@
+use crate::model::technology::prerequisite::Prerequisite;
+use crate::model::technology::tree::TechnologyTree;
+use crate::model::technology::{Technology, TechnologyId};
+use crate::usecase::creation::process_successors;
+
+/// A predecessor, that is already needed by another predecessor of the same technology.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedundantPredecessor {
+    technology: TechnologyId,
+    predecessor: TechnologyId,
+    implied_by: TechnologyId,
+}
+
+impl RedundantPredecessor {
+    pub fn new(
+        technology: TechnologyId,
+        predecessor: TechnologyId,
+        implied_by: TechnologyId,
+    ) -> Self {
+        RedundantPredecessor {
+            technology,
+            predecessor,
+            implied_by,
+        }
+    }
+
+    pub fn technology(&self) -> TechnologyId {
+        self.technology
+    }
+
+    pub fn predecessor(&self) -> TechnologyId {
+        self.predecessor
+    }
+
+    pub fn implied_by(&self) -> TechnologyId {
+        self.implied_by
+    }
+}
+
+/// Finds the direct predecessors, that are implied transitively by another direct predecessor.
+/// Only predecessors needed in any case are checked, so alternatives of OR-groups are kept.
+pub fn find_redundant_predecessors(tree: &TechnologyTree) -> Vec<RedundantPredecessor> {
+    let required = calculate_required(tree);
+    let mut redundant_list = Vec::new();
+
+    for technology in tree.technologies() {
+        let direct = get_direct_predecessors(technology);
+
+        for predecessor in &direct {
+            let implied_by = direct
+                .iter()
+                .find(|other| required[other.id()][predecessor.id()]);
+
+            if let Some(implied_by) = implied_by {
+                redundant_list.push(RedundantPredecessor::new(
+                    *technology.id(),
+                    *predecessor,
+                    *implied_by,
+                ));
+            }
+        }
+    }
+
+    redundant_list
+}
+
+/// Returns a tree without redundant predecessors, which needs the same technologies as before.
+pub fn reduce_transitively(tree: &TechnologyTree) -> TechnologyTree {
+    let redundant_list = find_redundant_predecessors(tree);
+
+    let prerequisite_list: Vec<Prerequisite<TechnologyId>> = tree
+        .technologies()
+        .iter()
+        .map(|technology| {
+            let is_redundant = |child: &Prerequisite<TechnologyId>| {
+                redundant_list.iter().any(|redundant| {
+                    redundant.technology == *technology.id()
+                        && *child == Prerequisite::Technology(redundant.predecessor)
+                })
+            };
+
+            match technology.prerequisite() {
+                Prerequisite::All(children) => Prerequisite::All(
+                    children
+                        .iter()
+                        .filter(|child| !is_redundant(child))
+                        .cloned()
+                        .collect(),
+                ),
+                prerequisite => prerequisite.clone(),
+            }
+        })
+        .collect();
+    let successors_list = process_successors(&prerequisite_list);
+
+    let technologies = tree
+        .technologies()
+        .iter()
+        .zip(prerequisite_list)
+        .zip(successors_list)
+        .map(|((technology, prerequisite), successors)| {
+            Technology::new(
+                *technology.id(),
+                technology.name().clone(),
+                prerequisite,
+                successors,
+                technology.cost(),
+            )
+            .with_pin(*technology.pin())
+            .with_era(technology.era().map(str::to_string))
+            .with_category(technology.category().map(str::to_string))
+            .with_description(technology.description().map(str::to_string))
+            .with_icon(technology.icon().map(str::to_string))
+        })
+        .collect();
+
+    TechnologyTree::new(technologies).with_eras(tree.eras().clone())
+}
+
+/// The predecessors, that are direct children of the top level [`Prerequisite::All`].
+fn get_direct_predecessors(technology: &Technology) -> Vec<TechnologyId> {
+    match technology.prerequisite() {
+        Prerequisite::All(children) => children
+            .iter()
+            .filter_map(|child| match child {
+                Prerequisite::Technology(id) => Some(*id),
+                _ => None,
+            })
+            .collect(),
+        Prerequisite::Technology(id) => vec![*id],
+        Prerequisite::Any(_) => vec![],
+    }
+}
+
+/// Calculates for each technology, which technologies are needed in any case to research it.
+fn calculate_required(tree: &TechnologyTree) -> Vec<Vec<bool>> {
+    let len = tree.technologies().len();
+    let mut required: Vec<Option<Vec<bool>>> = vec![None; len];
+    let mut is_in_progress = vec![false; len];
+
+    for technology in tree.technologies() {
+        calculate_required_of_technology(
+            tree,
+            *technology.id(),
+            &mut required,
+            &mut is_in_progress,
+        );
+    }
+
+    required.into_iter().map(Option::unwrap).collect()
+}
+
+fn calculate_required_of_technology(
+    tree: &TechnologyTree,
+    id: TechnologyId,
+    required: &mut Vec<Option<Vec<bool>>>,
+    is_in_progress: &mut Vec<bool>,
+) -> Vec<bool> {
+    if let Some(result) = &required[id.id()] {
+        return result.clone();
+    }
+
+    let len = tree.technologies().len();
+
+    // A cycle through an OR-group needs nothing, to stay on the safe side.
+    if is_in_progress[id.id()] {
+        return vec![false; len];
+    }
+
+    is_in_progress[id.id()] = true;
+    let prerequisite = tree.get(id).unwrap().prerequisite();
+    let result = calculate_required_of_prerequisite(tree, prerequisite, required, is_in_progress);
+    is_in_progress[id.id()] = false;
+    required[id.id()] = Some(result.clone());
+
+    result
+}
+
+fn calculate_required_of_prerequisite(
+    tree: &TechnologyTree,
+    prerequisite: &Prerequisite<TechnologyId>,
+    required: &mut Vec<Option<Vec<bool>>>,
+    is_in_progress: &mut Vec<bool>,
+) -> Vec<bool> {
+    let len = tree.technologies().len();
+
+    match prerequisite {
+        Prerequisite::Technology(id) => {
+            let mut result = calculate_required_of_technology(tree, *id, required, is_in_progress);
+            result[id.id()] = true;
+            result
+        }
+        Prerequisite::All(children) => {
+            let mut result = vec![false; len];
+
+            for child in children {
+                let child_result =
+                    calculate_required_of_prerequisite(tree, child, required, is_in_progress);
+
+                for (value, child_value) in result.iter_mut().zip(child_result) {
+                    *value |= child_value;
+                }
+            }
+
+            result
+        }
+        Prerequisite::Any(children) => {
+            let mut result = vec![!children.is_empty(); len];
+
+            for child in children {
+                let child_result =
+                    calculate_required_of_prerequisite(tree, child, required, is_in_progress);
+
+                for (value, child_value) in result.iter_mut().zip(child_result) {
+                    *value &= child_value;
+                }
+            }
+
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::technology::Input;
+    use crate::usecase::creation::create_tree;
+
+    #[test]
+    fn test_find_redundant_predecessors() {
+        let tree = create_example_tree();
+
+        assert_eq!(
+            find_redundant_predecessors(&tree),
+            vec![
+                RedundantPredecessor::new(id(4), id(1), id(2)),
+                RedundantPredecessor::new(id(4), id(5), id(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alternatives_are_not_redundant() {
+        let tree = create_tree(vec![
+            Input::test("t0", vec![]),
+            Input::test("t1", vec![]),
+            Input::with_prerequisite("t2", Prerequisite::any(vec!["t0", "t1"])),
+            Input::test("t3", vec!["t2", "t0"]),
+            Input::with_prerequisite(
+                "t4",
+                Prerequisite::Any(vec![
+                    Prerequisite::all(vec!["t0", "t1"]),
+                    Prerequisite::all(vec!["t0", "t2"]),
+                ]),
+            ),
+            Input::test("t5", vec!["t4", "t0", "t1"]),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            find_redundant_predecessors(&tree),
+            vec![RedundantPredecessor::new(id(5), id(0), id(4))]
+        );
+    }
+
+    #[test]
+    fn test_reduce_transitively() {
+        let tree = reduce_transitively(&create_example_tree());
+
+        assert_eq!(
+            tree,
+            create_tree(vec![
+                Input::test("Technology 0", vec![]),
+                Input::test("Technology 1", vec![]),
+                Input::test(
+                    "Technology 2",
+                    vec!["Technology 0", "Technology 1", "Technology 5"]
+                ),
+                Input::test("Technology 3", vec!["Technology 2"]),
+                Input::test("Technology 4", vec!["Technology 2"]),
+                Input::test("Technology 5", vec![]),
+            ])
+            .unwrap()
+        );
+        assert!(find_redundant_predecessors(&tree).is_empty());
+    }
+
+    fn create_example_tree() -> TechnologyTree {
+        create_tree(vec![
+            Input::test("Technology 0", vec![]),
+            Input::test("Technology 1", vec![]),
+            Input::test(
+                "Technology 2",
+                vec!["Technology 0", "Technology 1", "Technology 5"],
+            ),
+            Input::test("Technology 3", vec!["Technology 2"]),
+            Input::test(
+                "Technology 4",
+                vec!["Technology 2", "Technology 1", "Technology 5"],
+            ),
+            Input::test("Technology 5", vec![]),
+        ])
+        .unwrap()
+    }
+
+    fn id(id: usize) -> TechnologyId {
+        TechnologyId::new(id)
+    }
+}
@@ -1,11 +1,32 @@
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
 use structopt::StructOpt;
+use tech_tree::export::dot::DotExporter;
+use tech_tree::export::mermaid::MermaidExporter;
+use tech_tree::export::plantuml::PlantUmlExporter;
+use tech_tree::export::ExportOptions;
+use tech_tree::model::technology::research::ResearchState;
+use tech_tree::model::technology::tree::TechnologyTree;
+use tech_tree::rendering::layout::layered::{CrossingHeuristic, LayeredLayout};
+use tech_tree::rendering::orientation::Orientation;
+use tech_tree::rendering::routing::EdgeRouting;
 use tech_tree::rendering::tree::TreeRenderer;
-use tech_tree::usecase::validation::no_cycles::validate_no_cycles;
+use tech_tree::usecase::analysis::calculate_depth;
+use tech_tree::usecase::ranks::collapse_ranks;
+use tech_tree::usecase::validation::redundancy::{
+    find_redundant_predecessors, reduce_transitively,
+};
 use tech_tree_serde::definition::technology::tree::TechnologyTreeDefinition;
-use tech_tree_serde::io::read;
+use tech_tree_serde::diagnostic::format_diagnostic;
+use tech_tree_serde::io::{parse, read, read_string, write};
+use tech_tree_serde::location::TreeLocations;
+use tech_tree_svg::font::Font;
+use tech_tree_svg::pdf::{PaperSize, PdfOptions};
+use tech_tree_svg::raster::PngOptions;
+use tech_tree_svg::theme::Theme;
 use tech_tree_svg::SvgBuilder;
+use tech_tree_terminal::{Charset, TerminalRenderer, PADDING};
 
 #[derive(StructOpt)]
 #[structopt(name = "tech_tree_cli")]
@@ -14,28 +35,405 @@ struct Cli {
     /// The path of the [`TechnologyTreeDefinition`].
     #[structopt(parse(from_os_str))]
     path: PathBuf,
+    /// Displays the research cost inside the box of each technology.
+    #[structopt(short, long)]
+    cost: bool,
+    /// Combines each series of contiguous ranks into a single box.
+    #[structopt(long)]
+    collapse_ranks: bool,
+    /// The names of the researched technologies. Colors each technology by its research status.
+    #[structopt(short, long)]
+    researched: Vec<String>,
+    /// Arranges the rows in id order with `simple` or minimises crossing links with `barycenter` or `median`.
+    #[structopt(long, default_value = "simple", possible_values = &["simple", "barycenter", "median"])]
+    layout: String,
+    /// The direction of the links: `top-down`, `left-right`, `bottom-up` or `right-left`.
+    #[structopt(long, default_value = "top-down", possible_values = &["top-down", "left-right", "bottom-up", "right-left"])]
+    orientation: String,
+    /// Routes links horizontally & vertically around the boxes.
+    #[structopt(long)]
+    orthogonal: bool,
+    /// Renders a background band behind the technologies of each era.
+    #[structopt(long)]
+    eras: bool,
+    /// The path of a yaml file with the [`Theme`] of the svg.
+    #[structopt(long, parse(from_os_str))]
+    theme: Option<PathBuf>,
+    /// The path of a TrueType or OpenType font to measure the text with. Uses DejaVu Sans by default.
+    #[structopt(long, parse(from_os_str))]
+    font: Option<PathBuf>,
+    /// Wraps the text of a technology into multiple lines, if it is wider than this.
+    #[structopt(long)]
+    max_width: Option<u32>,
+    /// Embeds the icons into the svg instead of linking them. Their paths are relative to the yaml file.
+    #[structopt(long)]
+    embed_icons: bool,
+    /// The path of the output file. Its extension selects the format:
+    /// `svg`, `png`, `pdf`, `html`, `dot`, `mmd` for Mermaid, `md` for a Markdown code block with Mermaid or `puml` for PlantUML.
+    /// The interactive html page supports pan, zoom, search & highlighting the predecessors & successors.
+    /// All other tools calculate their own layout.
+    #[structopt(short, long, default_value = "output.svg", parse(from_os_str))]
+    output: PathBuf,
+    /// Multiplies the size of a png or pdf.
+    #[structopt(long, default_value = "1")]
+    scale: f32,
+    /// The resolution of a png. Values above 96 enlarge it, like the scale.
+    #[structopt(long, default_value = "96")]
+    dpi: f32,
+    /// The paper of a pdf: `a0` to `a5`, `letter`, `legal`, `tabloid` or a size in millimeters like `600x400`.
+    /// Larger trees are split into overlapping pages.
+    #[structopt(long, default_value = "a4")]
+    paper: PaperSize,
+    /// Turns the paper of a pdf, so that its longer side is horizontal.
+    #[structopt(long)]
+    landscape: bool,
+    /// The blank border of each page of a pdf in millimeters. Multiple pages need at least 8 for their marks & labels.
+    #[structopt(long, default_value = "10")]
+    margin: f32,
+    /// The width of the area printed on both of two neighbouring pages of a pdf in millimeters.
+    #[structopt(long, default_value = "10")]
+    overlap: f32,
+    /// Scales the tree to fit a single page of a pdf.
+    #[structopt(long)]
+    fit_to_page: bool,
+    /// Falls back to the fonts of the system for characters missing in the font of a png or pdf.
+    #[structopt(long)]
+    system_fonts: bool,
+    /// Prints the tree to the terminal instead of exporting it.
+    #[structopt(long)]
+    preview: bool,
+    /// Uses only ASCII characters for the preview.
+    #[structopt(long)]
+    ascii: bool,
+    /// Writes the tech tree without redundant predecessors to this path.
+    #[structopt(long, parse(from_os_str))]
+    reduce: Option<PathBuf>,
+}
+
+/// The formats of the output file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Svg,
+    Png,
+    Pdf,
+    Html,
+    Dot,
+    Mermaid,
+    /// Mermaid inside a Markdown code block.
+    Markdown,
+    PlantUml,
+}
+
+fn get_format(path: &Path) -> Result<Format> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("svg") => Ok(Format::Svg),
+        Some("png") => Ok(Format::Png),
+        Some("pdf") => Ok(Format::Pdf),
+        Some("html") => Ok(Format::Html),
+        Some("dot") | Some("gv") => Ok(Format::Dot),
+        Some("mmd") => Ok(Format::Mermaid),
+        Some("md") => Ok(Format::Markdown),
+        Some("puml") | Some("plantuml") => Ok(Format::PlantUml),
+        _ => bail!("Unsupported output format of {:?}", path),
+    }
+}
+
+fn create_research_state(tree: &TechnologyTree, names: &[String]) -> Result<ResearchState> {
+    let mut ids = names
+        .iter()
+        .map(|name| {
+            tree.find(name)
+                .map(|technology| *technology.id())
+                .ok_or_else(|| anyhow!("Researched technology `{}` is unknown", name))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let depth = calculate_depth(tree);
+    ids.sort_by_key(|id| depth[id.id()]);
+
+    let mut state = ResearchState::new(tree);
+
+    for id in ids {
+        state.research(tree, id)?;
+    }
+
+    Ok(state)
+}
+
+fn get_orientation(args: &Cli) -> Orientation {
+    match args.orientation.as_str() {
+        "left-right" => Orientation::LeftRight,
+        "bottom-up" => Orientation::BottomUp,
+        "right-left" => Orientation::RightLeft,
+        _ => Orientation::TopDown,
+    }
+}
+
+/// Exports the technologies & their links into a text format, but not the layout.
+fn export_text(args: &Cli, tree: &TechnologyTree, format: Format) -> Result<()> {
+    let collapsed;
+    let tree = if args.collapse_ranks {
+        collapsed = collapse_ranks(tree)?.0;
+        &collapsed
+    } else {
+        tree
+    };
+    let options = ExportOptions::default()
+        .with_cost(args.cost)
+        .with_categories(true)
+        .with_orientation(get_orientation(args));
+
+    let text = match format {
+        Format::Mermaid | Format::Markdown => MermaidExporter::new(options)
+            .with_fence(format == Format::Markdown)
+            .export(tree),
+        Format::PlantUml => PlantUmlExporter::new(options).export(tree)?,
+        _ => DotExporter::new(options).with_ranks(true).export(tree),
+    };
+
+    println!("Export to {:?}", args.output);
+
+    fs::write(&args.output, text).context(format!("Failed to export to {:?}", args.output))
+}
+
+fn get_research_state(args: &Cli, tree: &TechnologyTree) -> Result<Option<ResearchState>> {
+    if args.researched.is_empty() {
+        Ok(None)
+    } else {
+        create_research_state(tree, &args.researched).map(Some)
+    }
+}
+
+/// Prints the tree with the same layout as the svg, but with orthogonal links.
+fn print_preview(args: &Cli, tree: &TechnologyTree) -> Result<()> {
+    let state = get_research_state(args, tree)?;
+    let charset = if args.ascii {
+        Charset::Ascii
+    } else {
+        Charset::Unicode
+    };
+    let mut renderer = TerminalRenderer::new(charset);
+    let mut tree_renderer =
+        create_tree_renderer(args, PADDING).with_routing(EdgeRouting::Orthogonal);
+
+    tree_renderer.render(&mut renderer, tree, state.as_ref())?;
+
+    print!("{}", renderer.export_as_string());
+
+    Ok(())
+}
+
+fn create_tree_renderer(args: &Cli, padding: u32) -> TreeRenderer {
+    let orientation = get_orientation(args);
+    let routing = if args.orthogonal {
+        EdgeRouting::Orthogonal
+    } else {
+        EdgeRouting::Direct
+    };
+    let tree_renderer = TreeRenderer::new(padding)
+        .with_cost(args.cost)
+        .with_collapsed_ranks(args.collapse_ranks)
+        .with_orientation(orientation)
+        .with_routing(routing)
+        .with_eras(args.eras);
+
+    match args.layout.as_str() {
+        "barycenter" => {
+            tree_renderer.with_layout(LayeredLayout::new(CrossingHeuristic::Barycenter))
+        }
+        "median" => tree_renderer.with_layout(LayeredLayout::new(CrossingHeuristic::Median)),
+        _ => tree_renderer,
+    }
 }
 
 fn main() -> Result<()> {
     let args = Cli::from_args();
+    let format = get_format(&args.output)?;
 
     println!("Import tech tree from {:?}", args.path);
 
-    let definition: TechnologyTreeDefinition = read(&args.path)?;
-    let unvalidated = definition.to_model()?;
-    let tree = validate_no_cycles(unvalidated)?;
+    let source = read_string(&args.path)?;
+    let definition: TechnologyTreeDefinition = parse(&source, &args.path)?;
+    let tree = match definition.to_validated_model() {
+        Ok(tree) => tree,
+        Err(diagnostics) => {
+            let locations = TreeLocations::parse(&source)?;
+            let path = args.path.display().to_string();
+
+            for diagnostic in &diagnostics {
+                eprintln!(
+                    "{}\n",
+                    format_diagnostic(diagnostic, &locations, &source, &path)
+                );
+            }
 
+            bail!("Found {} error(s) in {:?}", diagnostics.len(), args.path);
+        }
+    };
+
+    let get_name = |id| tree.get(id).unwrap().name().get_full();
+
+    for redundant in find_redundant_predecessors(&tree) {
+        eprintln!(
+            "warning: Predecessor `{}` of `{}` is already implied by `{}`",
+            get_name(redundant.predecessor()),
+            get_name(redundant.technology()),
+            get_name(redundant.implied_by())
+        );
+    }
+
+    if let Some(path) = &args.reduce {
+        println!("Write reduced tech tree to {:?}", path);
+
+        let reduced = reduce_transitively(&tree);
+        write(&TechnologyTreeDefinition::from_model(&reduced), path)?;
+    }
+
+    if args.preview {
+        return print_preview(&args, &tree);
+    }
+
+    match format {
+        Format::Svg | Format::Png | Format::Pdf | Format::Html => export_svg(&args, &tree, format),
+        _ => export_text(&args, &tree, format),
+    }
+}
+
+/// Renders the tree with the layout of [`TreeRenderer`].
+fn export_svg(args: &Cli, tree: &TechnologyTree, format: Format) -> Result<()> {
     println!(
         "Render tech tree with {} technologies",
         tree.technologies().len()
     );
 
-    let mut builder = SvgBuilder::new(10, 10);
-    let mut tree_renderer = TreeRenderer::new(20);
+    let state = get_research_state(args, tree)?;
 
-    tree_renderer.render(&mut builder, &tree);
+    let theme: Theme = match &args.theme {
+        Some(path) => read(path)?,
+        None => Theme::default(),
+    };
+    let font = match &args.font {
+        Some(path) => Font::load(path)?,
+        None => Font::bundled(),
+    };
 
-    builder.export("output.svg")?;
+    if let Some(family) = font.family() {
+        for other in theme.get_font_families() {
+            if other != family {
+                eprintln!(
+                    "warning: Font family `{}` of the theme is measured with `{}`. Pass its file with `--font`, so the text fits the boxes",
+                    other, family
+                );
+            }
+        }
+    }
+    let mut builder = SvgBuilder::new(10, 10).with_theme(theme).with_font(font);
 
-    Ok(())
+    if let Some(max_width) = args.max_width {
+        builder = builder.with_max_text_width(max_width);
+    }
+
+    if format == Format::Html {
+        builder = builder.with_data_attributes();
+    }
+
+    let directory = args.path.parent().unwrap_or_else(|| Path::new(""));
+    let output_directory = args.output.parent().unwrap_or_else(|| Path::new(""));
+
+    for icon in tree.technologies().iter().filter_map(|t| t.icon()) {
+        if args.embed_icons {
+            builder.embed_icon(icon, &directory.join(icon))?;
+        } else if !icon.contains("://") {
+            let href = get_relative_href(&directory.join(icon), output_directory)?;
+            builder.link_icon(icon, &href);
+        }
+    }
+
+    let mut tree_renderer = create_tree_renderer(args, 20);
+
+    tree_renderer.render(&mut builder, tree, state.as_ref())?;
+
+    println!("Export to {:?}", args.output);
+
+    match format {
+        Format::Html => {
+            let title = args
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy())
+                .unwrap_or_default();
+            builder.export_html(&args.output, &title)
+        }
+        Format::Png => {
+            let mut options = PngOptions::default()
+                .with_scale(args.scale)
+                .with_dpi(args.dpi)
+                .with_resources_dir(output_directory.to_path_buf());
+
+            if args.system_fonts {
+                options = options.with_system_fonts();
+            }
+            builder.export_png(&args.output, &options)
+        }
+        Format::Pdf => {
+            let mut options = PdfOptions::default()
+                .with_paper(args.paper)
+                .with_margin(args.margin)
+                .with_overlap(args.overlap)
+                .with_scale(args.scale)
+                .with_resources_dir(output_directory.to_path_buf());
+
+            if args.landscape {
+                options = options.with_landscape();
+            }
+
+            if args.fit_to_page {
+                options = options.with_fit_to_page();
+            }
+
+            if args.system_fonts {
+                options = options.with_system_fonts();
+            }
+
+            builder.export_pdf(&args.output, &options)
+        }
+        _ => builder.export(&args.output),
+    }
+}
+
+/// Returns the path relative to a directory with `/` as separator, e.g. to link an icon from the output file.
+/// Relative paths start at the current directory.
+fn get_relative_href(path: &Path, directory: &Path) -> Result<String> {
+    let current = std::env::current_dir().context("Failed to get the current directory")?;
+    let path = normalize(&current.join(path));
+    let directory = normalize(&current.join(directory));
+    let common = path
+        .iter()
+        .zip(&directory)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let parts: Vec<&str> = std::iter::repeat_n("..", directory.len() - common)
+        .chain(path[common..].iter().map(String::as_str))
+        .collect();
+
+    Ok(parts.join("/"))
+}
+
+/// Returns the names of the components of a path after resolving `.` & `..`.
+fn normalize(path: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => {
+                names.push(prefix.as_os_str().to_string_lossy().to_string())
+            }
+            Component::ParentDir => {
+                names.pop();
+            }
+            Component::Normal(name) => names.push(name.to_string_lossy().to_string()),
+            Component::RootDir | Component::CurDir => {}
+        }
+    }
+
+    names
 }
@@ -49,3 +49,527 @@ technologies:\n
 
     Ok(())
 }
+
+#[test]
+fn success_with_cost() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = NamedTempFile::new()?;
+    writeln!(
+        file,
+        "---\n
+technologies:\n
+  - name: Technology 0\n
+    predecessors: []\n
+    cost: 100"
+    )?;
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(file.path()).arg("--cost");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn success_with_researched() -> Result<(), Box<dyn std::error::Error>> {
+    let file = create_file_with_2_technologies()?;
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(file.path())
+        .arg("--researched")
+        .arg("Technology 1")
+        .arg("Technology 0");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn locked_researched() -> Result<(), Box<dyn std::error::Error>> {
+    let file = create_file_with_2_technologies()?;
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(file.path()).arg("--researched").arg("Technology 1");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Technology `Technology 1` is locked",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn unknown_researched() -> Result<(), Box<dyn std::error::Error>> {
+    let file = create_file_with_2_technologies()?;
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(file.path()).arg("--researched").arg("Unknown");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Researched technology `Unknown` is unknown",
+    ));
+
+    Ok(())
+}
+
+fn create_file_with_2_technologies() -> Result<NamedTempFile, Box<dyn std::error::Error>> {
+    let mut file = NamedTempFile::new()?;
+    writeln!(
+        file,
+        "---\n
+technologies:\n
+  - name: Technology 0\n
+    predecessors: []\n
+  - name: Technology 1\n
+    predecessors: [Technology 0]"
+    )?;
+
+    Ok(file)
+}
+
+#[test]
+fn success_with_collapsed_ranks() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = NamedTempFile::new()?;
+    writeln!(
+        file,
+        "---\n
+chain_ranks: true\n
+technologies:\n
+  - name: Armor 1\n
+    predecessors: []\n
+  - name: Armor 2\n
+    predecessors: []"
+    )?;
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(file.path()).arg("--collapse-ranks");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn missing_rank() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = NamedTempFile::new()?;
+    writeln!(
+        file,
+        "---\n
+chain_ranks: true\n
+technologies:\n
+  - name: Armor 1\n
+    predecessors: []\n
+  - name: Armor 3\n
+    predecessors: []"
+    )?;
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(file.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Rank `Armor 2` is missing"));
+
+    Ok(())
+}
+
+#[test]
+fn all_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = NamedTempFile::new()?;
+    writeln!(
+        file,
+        "---\n
+technologies:\n
+  - name: Technology 0\n
+    predecessors: [Unknown]\n
+  - name: Technology 0\n
+    predecessors: []\n
+  - name: Technology 1\n
+    predecessors: [Technology 2]\n
+  - name: Technology 2\n
+    predecessors: [Technology 1]"
+    )?;
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(file.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "error: Name `Technology 0` already exists",
+        ))
+        .stderr(predicate::str::contains(
+            "error: Predecessor `Unknown` is unknown",
+        ))
+        .stderr(predicate::str::contains(
+            "7 |     predecessors: [Unknown]\n  |                    ^^^^^^^",
+        ))
+        .stderr(predicate::str::contains(
+            "error: Technologies [\"Technology 1\", \"Technology 2\"] form a cycle",
+        ))
+        .stderr(predicate::str::contains("Found 3 error(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn reduce() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let reduced_path = dir.path().join("reduced.yaml");
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--reduce")
+        .arg(&reduced_path);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "warning: Predecessor `Technology 1` of `Technology 4` is already implied by `Technology 2`",
+    ));
+
+    let reduced = std::fs::read_to_string(&reduced_path)?;
+
+    assert!(reduced.contains("- name: Technology 4\n    predecessors:\n      - Technology 2\n"));
+
+    Ok(())
+}
+
+#[test]
+fn success_with_layered_layout() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--layout")
+        .arg("median");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn success_with_orientation() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--orientation")
+        .arg("left-right");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn success_with_orthogonal_links() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml").arg("--orthogonal");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn success_with_theme() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--theme")
+        .arg("../resources/theme.yaml");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn mismatched_font_family() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = NamedTempFile::new()?;
+    write!(file, "node:\n  font_family: serif\n")?;
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--theme")
+        .arg(file.path());
+    cmd.assert().success().stderr(predicate::str::contains(
+        "warning: Font family `serif` of the theme is measured with `DejaVu Sans`",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn success_with_max_width() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--max-width")
+        .arg("60");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn predecessor_in_later_era() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = NamedTempFile::new()?;
+    write!(
+        file,
+        "eras: [Ancient, Medieval]
+technologies:
+  - name: Bronzeworking
+    predecessors: [Castles]
+    era: Ancient
+  - name: Castles
+    predecessors: []
+    era: Medieval
+    category: Military
+"
+    )?;
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(file.path()).arg("--eras");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: Predecessor `Castles` of `Bronzeworking` belongs to a later era",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_font() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--font")
+        .arg("../resources/theme.yaml");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Failed to parse \"../resources/theme.yaml\"",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn embed_icons() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("tree.yaml");
+    std::fs::create_dir(dir.path().join("icons"))?;
+    std::fs::write(dir.path().join("icons/mining.svg"), "<svg/>")?;
+    std::fs::write(
+        &path,
+        "technologies:
+  - name: Mining
+    predecessors: []
+    icon: icons/mining.svg
+  - name: Smelting
+    predecessors: [Mining]
+    icon: icons/smelting.svg
+",
+    )?;
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(&path).arg("--embed-icons");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to read icon"))
+        .stderr(predicate::str::contains("smelting.svg"));
+
+    std::fs::write(dir.path().join("icons/smelting.svg"), "<svg/>")?;
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(&path).arg("--embed-icons");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn link_icons() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("tree.yaml");
+    let output = dir.path().join("output/tree.svg");
+    std::fs::create_dir(dir.path().join("output"))?;
+    std::fs::write(
+        &path,
+        "technologies:
+  - name: Mining
+    predecessors: []
+    icon: icons/mining.svg
+",
+    )?;
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg(&path).arg("-o").arg(&output);
+    cmd.assert().success();
+
+    let svg = std::fs::read_to_string(&output)?;
+
+    assert!(svg.contains("href=\"../icons/mining.svg\""));
+
+    Ok(())
+}
+
+#[test]
+fn export_html() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("tree.html");
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml").arg("-o").arg(&path);
+    cmd.assert().success();
+
+    let html = std::fs::read_to_string(&path)?;
+
+    assert!(html.contains("<title>example</title>"));
+    assert!(html.contains("data-name=\"Technology 4\" data-predecessors=\"2 1 5\""));
+
+    Ok(())
+}
+
+#[test]
+fn export_png() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("tree.png");
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("-o")
+        .arg(&path)
+        .args(["--scale", "2", "--dpi", "144", "--system-fonts"]);
+    cmd.assert().success();
+
+    let png = std::fs::read(&path)?;
+
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+    Ok(())
+}
+
+#[test]
+fn export_pdf() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("tree.pdf");
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("-o")
+        .arg(&path)
+        .args(["--paper", "a5", "--landscape", "--scale", "2"]);
+    cmd.assert().success();
+
+    let pdf = String::from_utf8_lossy(&std::fs::read(&path)?).to_string();
+
+    assert!(pdf.starts_with("%PDF"));
+    assert!(pdf.contains("/MediaBox [0 0 595.2756 419.52756]"));
+    assert!(pdf.contains("/Label"));
+    assert!(!pdf.contains("/Font"));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_paper() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .args(["-o", "output.pdf", "--paper", "b4"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown paper size of \"b4\""));
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_output_format() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--output")
+        .arg("output.txt");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Unsupported output format of \"output.txt\"",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn export_dot() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("tree.dot");
+
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--orientation")
+        .arg("left-right")
+        .arg("-o")
+        .arg(&path);
+    cmd.assert().success();
+
+    let dot = std::fs::read_to_string(&path)?;
+
+    assert!(dot.starts_with("digraph tech_tree {\n  rankdir=LR;\n"));
+    assert!(dot.contains("  t2 -> t4;\n"));
+
+    Ok(())
+}
+
+#[test]
+fn export_mermaid_and_plantuml() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+
+    for (file, expected) in [
+        ("tree.mmd", "flowchart TD\n"),
+        ("tree.md", "```mermaid\nflowchart TD\n"),
+        ("tree.puml", "@startuml\n"),
+    ] {
+        let path = dir.path().join(file);
+        let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+        cmd.arg("../resources/example.yaml").arg("-o").arg(&path);
+        cmd.assert().success();
+
+        let text = std::fs::read_to_string(&path)?;
+
+        assert!(text.starts_with(expected));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn plantuml_with_unsupported_orientation() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--orientation")
+        .arg("bottom-up")
+        .arg("-o")
+        .arg(dir.path().join("tree.puml"));
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Orientation BottomUp is not supported by PlantUML",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn preview() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tech_tree_cli")?;
+
+    cmd.arg("../resources/example.yaml")
+        .arg("--preview")
+        .arg("--ascii");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("| Technology 4  |"));
+
+    Ok(())
+}
@@ -1,36 +1,127 @@
+use crate::definition::technology::prerequisite::{
+    from_model_list, to_model_list, PrerequisiteDefinition,
+};
 use serde::{Deserialize, Serialize};
+use tech_tree::model::technology::pin::Pin;
+use tech_tree::model::technology::prerequisite::Prerequisite;
 use tech_tree::model::technology::tree::TechnologyTree;
 use tech_tree::model::technology::{Input, Technology};
 
+pub mod prerequisite;
 pub mod tree;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TechnologyDefinition {
     name: String,
-    predecessors: Vec<String>,
+    predecessors: Vec<PrerequisiteDefinition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cost: Option<u32>,
+    /// Pins the technology to a row instead of its depth.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    row: Option<u32>,
+    /// Pins the technology to a position inside its row.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    column: Option<u32>,
+    /// Must be declared in the `eras` of the [`TechnologyTreeDefinition`](tree::TechnologyTreeDefinition),
+    /// which defines their order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    era: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    /// Shown as a tooltip.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// The path of an image file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
 }
 
 impl TechnologyDefinition {
-    pub fn new(name: String, predecessors: Vec<String>) -> Self {
-        TechnologyDefinition { name, predecessors }
+    pub fn new(name: String, predecessors: Vec<PrerequisiteDefinition>, cost: Option<u32>) -> Self {
+        TechnologyDefinition {
+            name,
+            predecessors,
+            cost,
+            row: None,
+            column: None,
+            era: None,
+            category: None,
+            description: None,
+            icon: None,
+        }
+    }
+
+    pub fn with_pin(self, row: Option<u32>, column: Option<u32>) -> Self {
+        TechnologyDefinition {
+            row,
+            column,
+            ..self
+        }
+    }
+
+    pub fn with_era(self, era: Option<String>) -> Self {
+        TechnologyDefinition { era, ..self }
+    }
+
+    pub fn with_category(self, category: Option<String>) -> Self {
+        TechnologyDefinition { category, ..self }
+    }
+
+    pub fn with_description(self, description: Option<String>) -> Self {
+        TechnologyDefinition {
+            description,
+            ..self
+        }
+    }
+
+    pub fn with_icon(self, icon: Option<String>) -> Self {
+        TechnologyDefinition { icon, ..self }
+    }
+
+    pub fn test(name: &str, predecessors: Vec<&str>, cost: Option<u32>) -> Self {
+        TechnologyDefinition::new(
+            name.to_string(),
+            predecessors
+                .into_iter()
+                .map(|name| PrerequisiteDefinition::Technology(name.to_string()))
+                .collect(),
+            cost,
+        )
     }
 
     pub fn to_model(self) -> Input {
-        Input::new(self.name, self.predecessors)
+        Input::new(
+            self.name,
+            Prerequisite::All(to_model_list(self.predecessors)),
+            self.cost.unwrap_or_default(),
+        )
+        .with_pin(Pin::new(self.row, self.column))
+        .with_era(self.era)
+        .with_category(self.category)
+        .with_description(self.description)
+        .with_icon(self.icon)
     }
 
     pub fn from_model(technology: &Technology, tree: &TechnologyTree) -> TechnologyDefinition {
+        let prerequisite = technology.prerequisite().map(&|id| {
+            tree.get(*id)
+                .map(|technology| technology.name().get_full().to_string())
+                .unwrap_or_else(|| "UNKNOWN".to_string())
+        });
+        let predecessors = match &prerequisite {
+            Prerequisite::All(children) => from_model_list(children),
+            prerequisite => vec![PrerequisiteDefinition::from_model(prerequisite)],
+        };
+
         TechnologyDefinition::new(
             technology.name().get_full().to_string(),
-            technology
-                .predecessors()
-                .iter()
-                .map(|id| {
-                    tree.get(*id)
-                        .map(|technology| technology.name().get_full().to_string())
-                        .unwrap_or_else(|| "UNKNOWN".to_string())
-                })
-                .collect(),
+            predecessors,
+            Some(technology.cost()).filter(|cost| *cost > 0),
         )
+        .with_pin(technology.pin().row(), technology.pin().column())
+        .with_era(technology.era().map(str::to_string))
+        .with_category(technology.category().map(str::to_string))
+        .with_description(technology.description().map(str::to_string))
+        .with_icon(technology.icon().map(str::to_string))
     }
 }
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use tech_tree::model::technology::prerequisite::Prerequisite;
+
+/// A predecessor is either the name of a technology or a nested group:
+///
+/// ```yaml
+/// predecessors:
+///   - Technology 0
+///   - any:
+///       - all: [Technology 1, Technology 2]
+///       - Technology 3
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PrerequisiteDefinition {
+    Technology(String),
+    All { all: Vec<PrerequisiteDefinition> },
+    Any { any: Vec<PrerequisiteDefinition> },
+}
+
+impl PrerequisiteDefinition {
+    pub fn to_model(self) -> Prerequisite<String> {
+        match self {
+            PrerequisiteDefinition::Technology(name) => Prerequisite::Technology(name),
+            PrerequisiteDefinition::All { all } => Prerequisite::All(to_model_list(all)),
+            PrerequisiteDefinition::Any { any } => Prerequisite::Any(to_model_list(any)),
+        }
+    }
+
+    pub fn from_model(prerequisite: &Prerequisite<String>) -> Self {
+        match prerequisite {
+            Prerequisite::Technology(name) => PrerequisiteDefinition::Technology(name.clone()),
+            Prerequisite::All(children) => PrerequisiteDefinition::All {
+                all: from_model_list(children),
+            },
+            Prerequisite::Any(children) => PrerequisiteDefinition::Any {
+                any: from_model_list(children),
+            },
+        }
+    }
+}
+
+/// The list of predecessors of a technology is an implicit [`Prerequisite::All`].
+pub fn to_model_list(list: Vec<PrerequisiteDefinition>) -> Vec<Prerequisite<String>> {
+    list.into_iter()
+        .map(PrerequisiteDefinition::to_model)
+        .collect()
+}
+
+pub fn from_model_list(list: &[Prerequisite<String>]) -> Vec<PrerequisiteDefinition> {
+    list.iter()
+        .map(PrerequisiteDefinition::from_model)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let yaml = "
+- t0
+- any:
+    - all: [t1, t2]
+    - t3";
+        let list: Vec<PrerequisiteDefinition> = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(
+            Prerequisite::All(to_model_list(list)),
+            Prerequisite::All(vec![
+                Prerequisite::Technology("t0".to_string()),
+                Prerequisite::Any(vec![
+                    Prerequisite::all(vec!["t1".to_string(), "t2".to_string()]),
+                    Prerequisite::Technology("t3".to_string()),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_converting() {
+        let definition = PrerequisiteDefinition::Any {
+            any: vec![
+                PrerequisiteDefinition::All {
+                    all: vec![
+                        PrerequisiteDefinition::Technology("t1".to_string()),
+                        PrerequisiteDefinition::Technology("t2".to_string()),
+                    ],
+                },
+                PrerequisiteDefinition::Technology("t3".to_string()),
+            ],
+        };
+
+        assert_eq!(
+            PrerequisiteDefinition::from_model(&definition.clone().to_model()),
+            definition
+        );
+    }
+}
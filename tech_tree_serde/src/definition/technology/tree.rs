@@ -1,53 +1,154 @@
 use crate::definition::technology::TechnologyDefinition;
 use serde::{Deserialize, Serialize};
-use tech_tree::model::error::AddError;
+use tech_tree::model::error::{AddError, Diagnostic};
 use tech_tree::model::technology::tree::TechnologyTree;
-use tech_tree::usecase::creation::create_tree;
+use tech_tree::usecase::creation::{create_tree, create_tree_with_rank_chaining};
+use tech_tree::usecase::validation::pipeline::create_validated_tree;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TechnologyTreeDefinition {
+    /// Each rank of a ranked technology needs the previous rank.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    chain_ranks: bool,
+    /// The eras in chronological order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    eras: Vec<String>,
     technologies: Vec<TechnologyDefinition>,
 }
 
 impl TechnologyTreeDefinition {
-    pub fn new(technologies: Vec<TechnologyDefinition>) -> Self {
-        TechnologyTreeDefinition { technologies }
+    pub fn new(chain_ranks: bool, technologies: Vec<TechnologyDefinition>) -> Self {
+        TechnologyTreeDefinition {
+            chain_ranks,
+            eras: Vec::new(),
+            technologies,
+        }
+    }
+
+    pub fn with_eras(self, eras: Vec<String>) -> Self {
+        TechnologyTreeDefinition { eras, ..self }
     }
 
     pub fn to_model(self) -> Result<TechnologyTree, AddError> {
-        create_tree(
+        let input_list = self
+            .technologies
+            .into_iter()
+            .map(TechnologyDefinition::to_model)
+            .collect();
+
+        let tree = if self.chain_ranks {
+            create_tree_with_rank_chaining(input_list)
+        } else {
+            create_tree(input_list)
+        }?;
+
+        Ok(tree.with_eras(self.eras))
+    }
+
+    /// Converts to a validated model & returns all errors, if it fails.
+    pub fn to_validated_model(self) -> Result<TechnologyTree, Vec<Diagnostic>> {
+        create_validated_tree(
             self.technologies
                 .into_iter()
                 .map(TechnologyDefinition::to_model)
                 .collect(),
+            self.eras,
+            self.chain_ranks,
         )
     }
 
+    /// The implicit predecessors of ranked technologies become explicit.
     pub fn from_model(tree: &TechnologyTree) -> Self {
         Self::new(
+            false,
             tree.technologies()
                 .iter()
                 .map(|technology| TechnologyDefinition::from_model(technology, tree))
                 .collect(),
         )
+        .with_eras(tree.eras().clone())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::definition::technology::prerequisite::PrerequisiteDefinition;
 
     #[test]
     fn test_converting() {
-        let definition = TechnologyTreeDefinition::new(vec![
-            TechnologyDefinition::new("t0".to_string(), vec![]),
-            TechnologyDefinition::new("t1".to_string(), vec!["t0".to_string()]),
-            TechnologyDefinition::new("t2".to_string(), vec!["t0".to_string(), "t1".to_string()]),
-        ]);
+        let definition = TechnologyTreeDefinition::new(
+            false,
+            vec![
+                TechnologyDefinition::test("t0", vec![], None),
+                TechnologyDefinition::test("t1", vec!["t0"], Some(20)),
+                TechnologyDefinition::test("t2", vec!["t0", "t1"], Some(30))
+                    .with_pin(Some(3), Some(1))
+                    .with_era(Some("Ancient".to_string()))
+                    .with_category(Some("Military".to_string()))
+                    .with_description(Some("Unlocks swords".to_string()))
+                    .with_icon(Some("icons/t2.png".to_string())),
+                TechnologyDefinition::new(
+                    "t3".to_string(),
+                    vec![PrerequisiteDefinition::Any {
+                        any: vec![
+                            PrerequisiteDefinition::Technology("t1".to_string()),
+                            PrerequisiteDefinition::Technology("t2".to_string()),
+                        ],
+                    }],
+                    None,
+                ),
+            ],
+        )
+        .with_eras(vec!["Ancient".to_string()]);
 
         assert_eq!(
             TechnologyTreeDefinition::from_model(&definition.clone().to_model().unwrap()),
             definition
         )
     }
+
+    #[test]
+    fn test_chain_ranks() {
+        let definition = TechnologyTreeDefinition::new(
+            true,
+            vec![
+                TechnologyDefinition::test("Armor 1", vec![], None),
+                TechnologyDefinition::test("Armor 2", vec![], None),
+            ],
+        );
+
+        assert_eq!(
+            TechnologyTreeDefinition::from_model(&definition.to_model().unwrap()),
+            TechnologyTreeDefinition::new(
+                false,
+                vec![
+                    TechnologyDefinition::test("Armor 1", vec![], None),
+                    TechnologyDefinition::test("Armor 2", vec!["Armor 1"], None),
+                ],
+            )
+        )
+    }
+
+    #[test]
+    fn test_to_validated_model() {
+        let definition = TechnologyTreeDefinition::new(
+            false,
+            vec![
+                TechnologyDefinition::test("t0", vec!["t1"], None),
+                TechnologyDefinition::test("t1", vec!["t0", "t2"], None),
+            ],
+        );
+
+        assert_eq!(
+            definition.to_validated_model(),
+            Err(vec![
+                Diagnostic::new(AddError::UnknownPredecessor("t2".to_string()), Some(1)),
+                Diagnostic::new(
+                    AddError::Cycle(vec!["t0".to_string(), "t1".to_string()]),
+                    Some(0)
+                ),
+            ])
+        )
+    }
 }
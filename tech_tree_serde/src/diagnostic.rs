@@ -0,0 +1,156 @@
+use crate::location::{Location, TreeLocations};
+use tech_tree::model::error::{AddError, Diagnostic};
+use unicode_width::UnicodeWidthStr;
+
+/// Formats a diagnostic like a compiler error with the line of the source, that caused it:
+///
+/// ```text
+/// error: Predecessor `Foo` is unknown
+///  --> tree.yaml:12:9
+///    |
+/// 12 |       - Foo
+///    |         ^^^
+/// ```
+pub fn format_diagnostic(
+    diagnostic: &Diagnostic,
+    locations: &TreeLocations,
+    source: &str,
+    path: &str,
+) -> String {
+    let header = format!("error: {}", diagnostic);
+
+    let location = match find_location(diagnostic, locations) {
+        Some(location) => location,
+        None => return header,
+    };
+
+    let line = source.lines().nth(location.line() - 1).unwrap_or_default();
+    let number = location.line().to_string();
+    let indent = " ".repeat(number.len());
+    let prefix: String = line.chars().take(location.column() - 1).collect();
+    let underline_length = get_underline_length(line, location.column());
+
+    format!(
+        "{}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+        header,
+        indent,
+        path,
+        location.line(),
+        location.column(),
+        indent,
+        number,
+        line,
+        indent,
+        " ".repeat(prefix.width()),
+        "^".repeat(underline_length)
+    )
+}
+
+/// Errors about a predecessor point to its usage, all other errors to the name of the technology.
+fn find_location(diagnostic: &Diagnostic, locations: &TreeLocations) -> Option<Location> {
+    let technology = locations.get(diagnostic.index()?)?;
+
+    let location = match diagnostic.error() {
+        AddError::UnknownPredecessor(name) | AddError::PredecessorInLaterEra(_, name) => {
+            technology.get_predecessor(name)
+        }
+        _ => None,
+    };
+
+    location
+        .or_else(|| technology.name())
+        .or_else(|| Some(technology.definition()))
+}
+
+/// Underlines the scalar starting at the column, which is counted in characters.
+/// The underline is as wide as the scalar in the terminal.
+fn get_underline_length(line: &str, column: usize) -> usize {
+    let rest: String = line.chars().skip(column - 1).collect();
+    let rest = rest.trim_end();
+
+    let scalar = match rest.chars().next() {
+        Some(quote @ ('"' | '\'')) => rest[1..].find(quote).map(|end| &rest[..end + 2]),
+        _ => rest.find([',', ']']).map(|end| rest[..end].trim_end()),
+    };
+
+    scalar.unwrap_or(rest).width().max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "technologies:
+  - name: t0
+    predecessors: [t1, Unknown]
+  - name: \"t0\"
+";
+
+    #[test]
+    fn test_unknown_predecessor() {
+        let diagnostic =
+            Diagnostic::new(AddError::UnknownPredecessor("Unknown".to_string()), Some(0));
+
+        assert_eq!(
+            format(&diagnostic),
+            "error: Predecessor `Unknown` is unknown
+ --> tree.yaml:3:24
+  |
+3 |     predecessors: [t1, Unknown]
+  |                        ^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_quoted_name() {
+        let diagnostic = Diagnostic::new(AddError::NameExists("t0".to_string()), Some(1));
+
+        assert_eq!(
+            format(&diagnostic),
+            "error: Name `t0` already exists
+ --> tree.yaml:4:11
+  |
+4 |   - name: \"t0\"
+  |           ^^^^"
+        );
+    }
+
+    #[test]
+    fn test_wide_characters() {
+        let source = "technologies:
+  - name: 採掘
+    predecessors: [鍛冶, Unknown]
+";
+        let locations = TreeLocations::parse(source).unwrap();
+        let diagnostic =
+            Diagnostic::new(AddError::UnknownPredecessor("Unknown".to_string()), Some(0));
+
+        assert_eq!(
+            format_diagnostic(&diagnostic, &locations, source, "tree.yaml"),
+            "error: Predecessor `Unknown` is unknown
+ --> tree.yaml:3:24
+  |
+3 |     predecessors: [鍛冶, Unknown]
+  |                          ^^^^^^^"
+        );
+
+        let diagnostic = Diagnostic::new(AddError::NameExists("採掘".to_string()), Some(0));
+
+        assert!(
+            format_diagnostic(&diagnostic, &locations, source, "tree.yaml")
+                .ends_with("  - name: 採掘\n  |           ^^^^")
+        );
+    }
+
+    #[test]
+    fn test_without_location() {
+        let diagnostic = Diagnostic::new(AddError::MissingRank("A 2".to_string()), None);
+
+        assert_eq!(format(&diagnostic), "error: Rank `A 2` is missing");
+    }
+
+    fn format(diagnostic: &Diagnostic) -> String {
+        let locations = TreeLocations::parse(SOURCE).unwrap();
+        format_diagnostic(diagnostic, &locations, SOURCE, "tree.yaml")
+    }
+}
@@ -7,9 +7,16 @@ use std::io::Write;
 use std::path::Path;
 
 pub fn read<T: DeserializeOwned>(path: &Path) -> Result<T> {
-    let string = fs::read_to_string(path).context(format!("Failed to read {:?}", path))?;
-    let data: T = serde_yaml::from_str(&string).context(format!("Failed to parse {:?}", path))?;
-    Ok(data)
+    parse(&read_string(path)?, path)
+}
+
+pub fn read_string(path: &Path) -> Result<String> {
+    fs::read_to_string(path).context(format!("Failed to read {:?}", path))
+}
+
+/// Parses the content of a file. The path is only used for error messages.
+pub fn parse<T: DeserializeOwned>(string: &str, path: &Path) -> Result<T> {
+    serde_yaml::from_str(string).context(format!("Failed to parse {:?}", path))
 }
 
 pub fn write<T: Serialize>(object: &T, path: &Path) -> Result<()> {
@@ -32,8 +39,7 @@ mod tests {
     fn test_io() {
         let dir = tempdir().unwrap();
         let file_path = dir.path().join("test.yaml");
-        let definition =
-            TechnologyDefinition::new("t2".to_string(), vec!["t0".to_string(), "t1".to_string()]);
+        let definition = TechnologyDefinition::test("t2", vec!["t0", "t1"], Some(100));
 
         write(&definition, &file_path).expect("Writing failed");
 
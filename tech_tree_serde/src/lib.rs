@@ -1,2 +1,4 @@
 pub mod definition;
+pub mod diagnostic;
 pub mod io;
+pub mod location;
@@ -0,0 +1,262 @@
+use anyhow::{Context, Result};
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
+
+/// A position inside the source of a yaml file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Location {
+    /// Starts at 1.
+    line: usize,
+    /// Starts at 1.
+    column: usize,
+}
+
+impl Location {
+    pub fn new(line: usize, column: usize) -> Self {
+        Location { line, column }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl From<Marker> for Location {
+    fn from(marker: Marker) -> Self {
+        Location::new(marker.line(), marker.col() + 1)
+    }
+}
+
+/// The locations of a single [`TechnologyDefinition`](crate::definition::technology::TechnologyDefinition).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TechnologyLocations {
+    definition: Location,
+    name: Option<Location>,
+    predecessors: Vec<(String, Location)>,
+}
+
+impl TechnologyLocations {
+    fn new(definition: Location) -> Self {
+        TechnologyLocations {
+            definition,
+            name: None,
+            predecessors: Vec::new(),
+        }
+    }
+
+    pub fn definition(&self) -> Location {
+        self.definition
+    }
+
+    pub fn name(&self) -> Option<Location> {
+        self.name
+    }
+
+    /// Returns the location of the first usage of a predecessor, even inside nested groups.
+    pub fn get_predecessor(&self, name: &str) -> Option<Location> {
+        self.predecessors
+            .iter()
+            .find(|(predecessor, _)| predecessor == name)
+            .map(|(_, location)| *location)
+    }
+}
+
+/// The locations of all technologies of a [`TechnologyTreeDefinition`](crate::definition::technology::tree::TechnologyTreeDefinition) in the same order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TreeLocations {
+    technologies: Vec<TechnologyLocations>,
+}
+
+impl TreeLocations {
+    /// Finds the locations with a separate yaml parser, which can't match entries copied by aliases or merge keys.
+    /// Documents using them have no locations, instead of wrong ones.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut receiver = Receiver::default();
+
+        Parser::new(source.chars())
+            .load(&mut receiver, false)
+            .context("Failed to parse the locations")?;
+
+        if receiver.has_aliases {
+            return Ok(TreeLocations::default());
+        }
+
+        Ok(TreeLocations {
+            technologies: receiver.technologies,
+        })
+    }
+
+    pub fn get(&self, index: usize) -> Option<&TechnologyLocations> {
+        self.technologies.get(index)
+    }
+}
+
+enum Container {
+    Mapping {
+        key: Option<String>,
+        is_expecting_key: bool,
+    },
+    Sequence,
+}
+
+#[derive(Default)]
+struct Receiver {
+    stack: Vec<Container>,
+    technologies: Vec<TechnologyLocations>,
+    /// Does the document contain aliases or merge keys?
+    has_aliases: bool,
+}
+
+impl Receiver {
+    fn has_key(&self, index: usize, expected: &str) -> bool {
+        matches!(
+            self.stack.get(index),
+            Some(Container::Mapping { key: Some(key), .. }) if key == expected
+        )
+    }
+
+    /// Is the top of the stack the list of technologies?
+    fn is_in_technologies(&self) -> bool {
+        self.stack.len() == 2
+            && self.has_key(0, "technologies")
+            && matches!(self.stack[1], Container::Sequence)
+    }
+
+    /// Is the top of the stack the mapping of a technology or inside of it?
+    fn is_in_technology(&self) -> bool {
+        self.stack.len() >= 3
+            && self.has_key(0, "technologies")
+            && matches!(self.stack[1], Container::Sequence)
+    }
+
+    fn on_scalar(&mut self, value: String, location: Location) {
+        if let Some(Container::Mapping {
+            key,
+            is_expecting_key,
+        }) = self.stack.last_mut()
+        {
+            if *is_expecting_key {
+                self.has_aliases |= value == "<<";
+                *key = Some(value);
+                *is_expecting_key = false;
+                return;
+            }
+        }
+
+        if self.is_in_technology() {
+            let is_name = self.stack.len() == 3 && self.has_key(2, "name");
+            let is_predecessor = self.has_key(2, "predecessors")
+                && matches!(self.stack.last(), Some(Container::Sequence));
+            let technology = self.technologies.last_mut().unwrap();
+
+            if is_name {
+                technology.name = Some(location);
+            } else if is_predecessor {
+                technology.predecessors.push((value, location));
+            }
+        }
+
+        self.on_value_end();
+    }
+
+    fn on_container_start(&mut self, container: Container, location: Location) {
+        if self.is_in_technologies() {
+            self.technologies.push(TechnologyLocations::new(location));
+        }
+
+        self.stack.push(container);
+    }
+
+    fn on_container_end(&mut self) {
+        self.stack.pop();
+        self.on_value_end();
+    }
+
+    fn on_value_end(&mut self) {
+        if let Some(Container::Mapping {
+            is_expecting_key, ..
+        }) = self.stack.last_mut()
+        {
+            *is_expecting_key = true;
+        }
+    }
+}
+
+impl MarkedEventReceiver for Receiver {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        let location = Location::from(marker);
+
+        match event {
+            Event::Scalar(value, ..) => self.on_scalar(value, location),
+            Event::MappingStart(_) => self.on_container_start(
+                Container::Mapping {
+                    key: None,
+                    is_expecting_key: true,
+                },
+                location,
+            ),
+            Event::SequenceStart(_) => self.on_container_start(Container::Sequence, location),
+            Event::MappingEnd | Event::SequenceEnd => self.on_container_end(),
+            Event::Alias(_) => {
+                self.has_aliases = true;
+                self.on_value_end();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let source = "---
+chain_ranks: true
+technologies:
+  - name: t0
+    predecessors: []
+  - predecessors:
+      - t0
+      - any:
+          - all: [t1, t2]
+          - t3
+    name: \"t4\"
+";
+        let locations = TreeLocations::parse(source).unwrap();
+
+        let t0 = locations.get(0).unwrap();
+        assert_eq!(t0.definition(), Location::new(4, 9));
+        assert_eq!(t0.name(), Some(Location::new(4, 11)));
+
+        let t4 = locations.get(1).unwrap();
+        assert_eq!(t4.definition(), Location::new(6, 17));
+        assert_eq!(t4.name(), Some(Location::new(11, 11)));
+        assert_eq!(t4.get_predecessor("t0"), Some(Location::new(7, 9)));
+        assert_eq!(t4.get_predecessor("t2"), Some(Location::new(9, 23)));
+        assert_eq!(t4.get_predecessor("t3"), Some(Location::new(10, 13)));
+        assert_eq!(t4.get_predecessor("t5"), None);
+        assert_eq!(locations.get(2), None);
+    }
+
+    #[test]
+    fn test_parse_with_aliases() {
+        let source = "---
+technologies:
+  - &base
+    name: t0
+  - <<: *base
+    name: t1
+";
+
+        assert_eq!(
+            TreeLocations::parse(source).unwrap(),
+            TreeLocations::default()
+        );
+    }
+}
@@ -1,13 +1,31 @@
-use tech_tree::rendering::renderer::Renderer;
+use tech_tree::model::technology::TechnologyId;
+use tech_tree::rendering::renderer::{Renderer, TechnologyBox};
 use tech_tree_svg::SvgBuilder;
 
 fn main() {
     let mut builder = SvgBuilder::new(10, 10);
 
     builder.init(100, 150);
-    builder.render_technology("Tech 1", 50, 20);
-    builder.render_technology("Tech 2", 50, 70);
-    builder.render_link(vec![(50, 30), (50, 60)]);
+
+    for (text, y) in [("Tech 1", 20), ("Tech 2", 70)] {
+        let (width, height) = builder.get_size_of_technology(text, false);
+
+        builder.render_technology(&TechnologyBox {
+            id: TechnologyId::new(0),
+            predecessors: &[],
+            text,
+            x: 50,
+            y,
+            width,
+            height,
+            status: None,
+            category: None,
+            description: None,
+            icon: None,
+        });
+    }
+
+    builder.render_link(vec![(50, 30), (50, 60)], false);
 
     builder.export("builder.svg").expect("Failed test");
 }
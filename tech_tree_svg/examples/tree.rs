@@ -22,7 +22,9 @@ fn main() {
     let mut builder = SvgBuilder::new(10, 10);
     let mut tree_renderer = TreeRenderer::new(20);
 
-    tree_renderer.render(&mut builder, &tree);
+    tree_renderer
+        .render(&mut builder, &tree, None)
+        .expect("Failed test");
 
     builder.export("tree.svg").expect("Failed test");
 }
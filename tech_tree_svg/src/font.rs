@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use ttf_parser::{name_id, Face, GlyphId};
+
+#[cfg(feature = "bundled-font")]
+const BUNDLED_FONT: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+
+/// A TrueType or OpenType font to measure text with the advances of its glyphs.
+#[derive(Clone, Debug)]
+pub struct Font {
+    data: Vec<u8>,
+    family: Option<String>,
+    units_per_em: u32,
+    /// The advance of the glyph of each character in the cmap.
+    advances: HashMap<char, u16>,
+    /// The advance of the glyph used for characters without one.
+    missing_advance: u16,
+}
+
+impl Font {
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read(path).context(format!("Failed to read {:?}", path))?;
+        Self::from_bytes(data).context(format!("Failed to parse {:?}", path))
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let face = Face::parse(&data, 0).map_err(|error| anyhow!("Invalid font: {}", error))?;
+        let family = [name_id::TYPOGRAPHIC_FAMILY, name_id::FAMILY]
+            .iter()
+            .find_map(|id| {
+                face.names()
+                    .into_iter()
+                    .filter(|name| name.name_id == *id)
+                    .find_map(|name| name.to_string())
+            });
+        let mut advances = HashMap::new();
+
+        for subtable in face.tables().cmap.iter().flat_map(|cmap| cmap.subtables) {
+            subtable.codepoints(|codepoint| {
+                if let Some((c, glyph)) = char::from_u32(codepoint)
+                    .and_then(|c| face.glyph_index(c).map(|glyph| (c, glyph)))
+                {
+                    advances.insert(c, face.glyph_hor_advance(glyph).unwrap_or_default());
+                }
+            });
+        }
+
+        Ok(Font {
+            family,
+            units_per_em: face.units_per_em().max(1) as u32,
+            advances,
+            missing_advance: face.glyph_hor_advance(GlyphId(0)).unwrap_or_default(),
+            data,
+        })
+    }
+
+    /// DejaVu Sans, which is included in the binary with the default feature `bundled-font`.
+    #[cfg(feature = "bundled-font")]
+    pub fn bundled() -> Self {
+        Self::from_bytes(BUNDLED_FONT.to_vec()).unwrap()
+    }
+
+    /// Returns the name of the font family, that is used by the svg.
+    pub fn family(&self) -> Option<&str> {
+        self.family.as_deref()
+    }
+
+    /// The raw bytes of the font file.
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the width of a single line of text in pixels. Kerning is ignored.
+    /// Characters without a glyph use the advance of the missing glyph.
+    pub fn get_width(&self, text: &str, font_size: u32) -> u32 {
+        let advances: u32 = text
+            .chars()
+            .map(|c| *self.advances.get(&c).unwrap_or(&self.missing_advance) as u32)
+            .sum();
+
+        (advances * font_size).div_ceil(self.units_per_em)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_bundled() {
+        let font = Font::bundled();
+
+        assert_eq!(font.family(), Some("DejaVu Sans"));
+        assert_eq!(font.get_width("", 10), 0);
+        assert!(font.get_width("W", 10) > font.get_width("i", 10));
+        // `é` is 2 bytes, but a single glyph as wide as `e`
+        assert_eq!(font.get_width("é", 10), font.get_width("e", 10));
+        assert_eq!(
+            font.get_width("Métallurgie", 20),
+            font.get_width("Metallurgie", 20)
+        );
+    }
+
+    #[test]
+    fn test_invalid_font() {
+        assert!(Font::from_bytes(b"no font".to_vec()).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+//! A self-contained html page around the svg of a tech tree.
+
+const STYLE: &str = include_str!("style.css");
+const SCRIPT: &str = include_str!("script.js");
+
+/// Creates a page with the svg, a search box & inline scripts for pan, zoom & highlight.
+/// Clicking a technology highlights all its transitive predecessors & successors.
+///
+/// The svg must be created with [`SvgBuilder::with_data_attributes`](crate::SvgBuilder::with_data_attributes)
+/// to find the technologies & their predecessors.
+pub fn create_html(title: &str, svg: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<style>
+{}</style>
+</head>
+<body>
+<header>
+<input id=\"search\" type=\"search\" placeholder=\"Search technologies\" autocomplete=\"off\">
+<span id=\"matches\"></span>
+<button id=\"reset\" type=\"button\">Reset view</button>
+</header>
+<main id=\"tree\">
+{}
+</main>
+<script>
+{}</script>
+</body>
+</html>
+",
+        escape_html(title),
+        STYLE,
+        svg,
+        SCRIPT
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_html() {
+        let html = create_html("Research & Development", "<svg viewBox=\"0 0 10 10\"/>");
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Research &amp; Development</title>"));
+        assert!(html.contains("<main id=\"tree\">\n<svg viewBox=\"0 0 10 10\"/>\n</main>"));
+        assert!(html.contains(SCRIPT));
+    }
+}
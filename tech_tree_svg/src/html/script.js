@@ -0,0 +1,185 @@
+"use strict";
+
+const container = document.getElementById("tree");
+const svg = container.querySelector("svg");
+const search = document.getElementById("search");
+const matchCount = document.getElementById("matches");
+const initialViewBox = svg.getAttribute("viewBox");
+
+// The graph of the technologies from the data attributes of the svg
+const technologies = new Map();
+const predecessors = new Map();
+const successors = new Map();
+
+for (const node of svg.querySelectorAll(".technology")) {
+  const id = node.dataset.id;
+  const ids = node.dataset.predecessors.split(" ").filter((id) => id !== "");
+
+  technologies.set(id, node);
+  predecessors.set(id, ids);
+
+  for (const predecessor of ids) {
+    if (!successors.has(predecessor)) {
+      successors.set(predecessor, []);
+    }
+
+    successors.get(predecessor).push(id);
+  }
+}
+
+function collect(start, neighbours, result) {
+  const stack = [start];
+
+  while (stack.length > 0) {
+    for (const next of neighbours.get(stack.pop()) || []) {
+      if (!result.has(next)) {
+        result.add(next);
+        stack.push(next);
+      }
+    }
+  }
+}
+
+function clearSelection() {
+  svg.classList.remove("has-selection");
+
+  for (const node of technologies.values()) {
+    node.classList.remove("selected", "highlighted");
+  }
+}
+
+// Highlights a technology with all its transitive predecessors & successors
+function select(id) {
+  const related = new Set([id]);
+  collect(id, predecessors, related);
+  collect(id, successors, related);
+
+  clearSelection();
+  svg.classList.add("has-selection");
+  technologies.get(id).classList.add("selected");
+
+  for (const other of related) {
+    technologies.get(other).classList.add("highlighted");
+  }
+}
+
+// Pan & zoom by changing the view box
+function toSvgPoint(event) {
+  const point = svg.createSVGPoint();
+  point.x = event.clientX;
+  point.y = event.clientY;
+  return point.matrixTransform(svg.getScreenCTM().inverse());
+}
+
+function centerOn(node) {
+  const box = node.getBBox();
+  const viewBox = svg.viewBox.baseVal;
+  viewBox.x = box.x + box.width / 2 - viewBox.width / 2;
+  viewBox.y = box.y + box.height / 2 - viewBox.height / 2;
+}
+
+svg.addEventListener("wheel", (event) => {
+  event.preventDefault();
+
+  const scale = event.deltaY > 0 ? 1.1 : 1 / 1.1;
+  const point = toSvgPoint(event);
+  const viewBox = svg.viewBox.baseVal;
+
+  // The point under the cursor stays in place
+  viewBox.x = point.x - (point.x - viewBox.x) * scale;
+  viewBox.y = point.y - (point.y - viewBox.y) * scale;
+  viewBox.width *= scale;
+  viewBox.height *= scale;
+}, { passive: false });
+
+// Small movements still count as a click
+const DRAG_THRESHOLD = 4;
+let dragStart = null;
+let clickStart = null;
+let hasDragged = false;
+
+svg.addEventListener("pointerdown", (event) => {
+  dragStart = toSvgPoint(event);
+  clickStart = { x: event.clientX, y: event.clientY };
+  hasDragged = false;
+});
+
+svg.addEventListener("pointermove", (event) => {
+  if (dragStart === null) {
+    return;
+  }
+
+  const distance = Math.abs(event.clientX - clickStart.x) + Math.abs(event.clientY - clickStart.y);
+
+  if (hasDragged || distance > DRAG_THRESHOLD) {
+    const point = toSvgPoint(event);
+    const viewBox = svg.viewBox.baseVal;
+
+    if (!hasDragged) {
+      // Captured only while dragging, so that a click keeps its target
+      svg.setPointerCapture(event.pointerId);
+      hasDragged = true;
+      container.classList.add("dragging");
+    }
+
+    viewBox.x -= point.x - dragStart.x;
+    viewBox.y -= point.y - dragStart.y;
+  }
+});
+
+svg.addEventListener("pointerup", (event) => {
+  const wasClick = !hasDragged;
+  dragStart = null;
+  container.classList.remove("dragging");
+
+  if (svg.hasPointerCapture(event.pointerId)) {
+    svg.releasePointerCapture(event.pointerId);
+  }
+
+  if (wasClick) {
+    const node = event.target.closest(".technology");
+
+    if (node === null) {
+      clearSelection();
+    } else {
+      select(node.dataset.id);
+    }
+  }
+});
+
+// Search by name
+search.addEventListener("input", () => {
+  const query = search.value.trim().toLowerCase();
+  let count = 0;
+
+  for (const node of technologies.values()) {
+    const isMatch = query !== "" && node.dataset.name.toLowerCase().includes(query);
+    node.classList.toggle("match", isMatch);
+
+    if (isMatch) {
+      count += 1;
+    }
+  }
+
+  matchCount.textContent = query === "" ? "" : `${count} match(es)`;
+});
+
+search.addEventListener("keydown", (event) => {
+  if (event.key === "Enter") {
+    const node = svg.querySelector(".technology.match");
+
+    if (node !== null) {
+      select(node.dataset.id);
+      centerOn(node);
+    }
+  } else if (event.key === "Escape") {
+    search.value = "";
+    search.dispatchEvent(new Event("input"));
+    clearSelection();
+  }
+});
+
+document.getElementById("reset").addEventListener("click", () => {
+  svg.setAttribute("viewBox", initialViewBox);
+  clearSelection();
+});
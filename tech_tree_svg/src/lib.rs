@@ -1,59 +1,326 @@
+use crate::font::Font;
+use crate::pdf::PdfOptions;
+use crate::raster::PngOptions;
+use crate::theme::{ArrowHead, NodeStyle, Theme, DEFAULT_FILL};
 use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs;
 use std::io::BufWriter;
+use std::path::Path;
 use svg::node::element::path::Data;
-use svg::node::element::Text;
-use svg::node::element::{Definitions, Marker, Path, Rectangle};
+use svg::node::element::{Circle, Definitions, Group, Image, Marker, Path as PathNode};
+use svg::node::element::{Element, Text};
+use svg::node::element::{Rectangle, Title};
 use svg::{Document, Node};
-use tech_tree::rendering::renderer::Renderer;
+use tech_tree::model::technology::research::ResearchStatus;
+use tech_tree::rendering::renderer::{Renderer, TechnologyBox};
+use tech_tree::rendering::text::wrap_text;
+
+pub mod font;
+pub mod html;
+mod parsing;
+pub mod pdf;
+pub mod raster;
+pub mod theme;
+
+const DIMMED_OPACITY: f32 = 0.3;
+const JUNCTION_RADIUS: u32 = 4;
 
 pub struct SvgBuilder {
     document: Document,
     font_size: u32,
     text_padding: u32,
+    theme: Theme,
+    /// Wraps longer text into multiple lines, if present.
+    max_text_width: Option<u32>,
+    /// Approximates the width of each character with half the font size, if missing.
+    font: Option<Font>,
+    /// The categories in the order of their first appearance, which defines their color.
+    categories: Vec<String>,
+    era_count: usize,
+    /// The data URIs of the embedded icons & the hrefs of icons linked with another path.
+    /// All other icons are linked with their own path.
+    icons: HashMap<String, String>,
+    has_data_attributes: bool,
 }
 
 impl SvgBuilder {
     pub fn new(font_size: u32, text_padding: u32) -> Self {
+        let theme = Theme::default();
+
         Self {
-            document: Document::new().add(Self::create_definitions()),
+            document: Document::new().add(Self::create_definitions(&theme)),
             font_size,
             text_padding,
+            theme,
+            max_text_width: None,
+            font: None,
+            categories: Vec::new(),
+            era_count: 0,
+            icons: HashMap::new(),
+            has_data_attributes: false,
+        }
+    }
+
+    /// Replaces the default [`Theme`]. Must be called before rendering.
+    pub fn with_theme(self, theme: Theme) -> Self {
+        Self {
+            document: Document::new().add(Self::create_definitions(&theme)),
+            theme,
+            ..self
+        }
+    }
+
+    /// Measures the text with the glyphs of a font, which is also used by the svg,
+    /// unless the theme sets another font family.
+    pub fn with_font(self, font: Font) -> Self {
+        Self {
+            font: Some(font),
+            ..self
+        }
+    }
+
+    /// Wraps text wider than the maximum width into multiple lines. The padding is not included.
+    pub fn with_max_text_width(self, max_text_width: u32) -> Self {
+        Self {
+            max_text_width: Some(max_text_width),
+            ..self
+        }
+    }
+
+    /// Groups the nodes of each technology & adds its id, name & predecessors as data attributes,
+    /// which allows scripts to find them. See [`html::create_html`].
+    pub fn with_data_attributes(self) -> Self {
+        Self {
+            has_data_attributes: true,
+            ..self
         }
     }
 
-    fn create_definitions() -> Definitions {
-        let arrow_head_data = Data::new()
-            .move_to((0, 0))
-            .line_to((10, 7))
-            .line_to((0, 14))
-            .close();
+    /// Embeds an icon as a base64 data URI instead of linking it.
+    /// The path is the actual file of the icon, e.g. relative to the yaml file instead of the svg.
+    pub fn embed_icon(&mut self, icon: &str, path: &Path) -> Result<()> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let mime_type = match extension.as_str() {
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "svg" => "image/svg+xml",
+            "webp" => "image/webp",
+            _ => anyhow::bail!("Unsupported icon format of {:?}", path),
+        };
+        let bytes = fs::read(path).context(format!("Failed to read icon {:?}", path))?;
+        let uri = format!("data:{};base64,{}", mime_type, STANDARD.encode(bytes));
+
+        self.icons.insert(icon.to_string(), uri);
 
-        let arrow_head_path = Path::new().set("fill", "black").set("d", arrow_head_data);
+        Ok(())
+    }
+
+    /// Links an icon with another href, e.g. the path relative to the svg instead of the yaml file.
+    pub fn link_icon(&mut self, icon: &str, href: &str) {
+        self.icons.insert(icon.to_string(), href.to_string());
+    }
 
-        let arrow_head = Marker::new()
+    fn create_definitions(theme: &Theme) -> Definitions {
+        let color = escape(&theme.link.color);
+        let marker = Marker::new()
             .set("id", "head")
             .set("viewBox", (0, 0, 10, 10))
             .set("orient", "auto")
             .set("refX", 1)
-            .set("refY", 7)
-            .add(arrow_head_path);
+            .set("refY", 7);
+
+        let arrow_head = match theme.link.arrowhead {
+            ArrowHead::Triangle => {
+                let data = Data::new()
+                    .move_to((0, 0))
+                    .line_to((10, 7))
+                    .line_to((0, 14))
+                    .close();
+                marker.add(PathNode::new().set("fill", color).set("d", data))
+            }
+            ArrowHead::Open => {
+                let data = Data::new()
+                    .move_to((0, 0))
+                    .line_to((10, 7))
+                    .line_to((0, 14));
+                marker.set("overflow", "visible").add(
+                    PathNode::new()
+                        .set("fill", "none")
+                        .set("stroke", color)
+                        .set("stroke-width", 2)
+                        .set("d", data),
+                )
+            }
+            ArrowHead::Circle => marker.add(
+                Circle::new()
+                    .set("cx", 5)
+                    .set("cy", 7)
+                    .set("r", 4)
+                    .set("fill", color),
+            ),
+            ArrowHead::None => return Definitions::new(),
+        };
 
         Definitions::new().add(arrow_head)
     }
 
-    fn get_text_width(&self, text: &str) -> u32 {
-        let font_width = self.font_size / 2;
-        text.len() as u32 * font_width + 2 * self.text_padding
+    fn get_line_width(&self, line: &str) -> u32 {
+        match &self.font {
+            Some(font) => font.get_width(line, self.font_size),
+            None => line.chars().count() as u32 * (self.font_size / 2),
+        }
+    }
+
+    fn get_icon_size(&self) -> u32 {
+        self.font_size * 3 / 2
+    }
+
+    /// The horizontal space of an icon including the gap to the text.
+    fn get_icon_space(&self) -> u32 {
+        self.get_icon_size() + self.text_padding / 2
     }
 
-    fn get_text_height(&self) -> u32 {
-        self.font_size * 2
+    /// Creates the image of an icon, which is centered vertically at y.
+    fn create_icon(&self, icon: &str, left: u32, y: u32) -> Image {
+        let size = self.get_icon_size();
+        let href = self.icons.get(icon).map(String::as_str).unwrap_or(icon);
+
+        Image::new()
+            .set("x", left)
+            .set("y", y.saturating_sub(size / 2))
+            .set("width", size)
+            .set("height", size)
+            .set("href", escape(href))
     }
 
-    pub fn export(&self, path: &str) -> Result<()> {
+    fn get_line_height(&self) -> u32 {
+        self.font_size * 6 / 5
+    }
+
+    /// A single line has half the font size as padding above & below.
+    fn get_text_height(&self, line_count: usize) -> u32 {
+        self.font_size * 2 + line_count.saturating_sub(1) as u32 * self.get_line_height()
+    }
+
+    fn get_lines(&self, text: &str) -> Vec<String> {
+        match self.max_text_width {
+            Some(max_width) => wrap_text(text, max_width, |line| self.get_line_width(line)),
+            None => vec![text.to_string()],
+        }
+    }
+
+    /// The research status has priority over the fill of the node style including the override of the category.
+    /// Without both, categories use the next color of [`Theme::category_fills`].
+    fn get_fill_color(&mut self, status: Option<ResearchStatus>, category: Option<&str>) -> String {
+        match status {
+            Some(ResearchStatus::Researched) => self.theme.researched_fill.clone(),
+            Some(ResearchStatus::Available) => self.theme.available_fill.clone(),
+            Some(ResearchStatus::Locked) => self.theme.locked_fill.clone(),
+            None => match (self.theme.get_node_style(category).fill, category) {
+                (Some(fill), _) => fill,
+                (None, Some(category)) if !self.theme.category_fills.is_empty() => {
+                    let index = self.get_category_index(category);
+                    let fills = &self.theme.category_fills;
+                    fills[index % fills.len()].clone()
+                }
+                (None, _) => DEFAULT_FILL.to_string(),
+            },
+        }
+    }
+
+    fn get_category_index(&mut self, category: &str) -> usize {
+        match self.categories.iter().position(|other| other == category) {
+            Some(index) => index,
+            None => {
+                self.categories.push(category.to_string());
+                self.categories.len() - 1
+            }
+        }
+    }
+
+    /// Sets the optional font & text color of a node style.
+    /// The family of the style wins over the one of the measuring font.
+    fn apply_text_style(&self, mut text: Text, style: &NodeStyle) -> Text {
+        let font_family = style
+            .font_family
+            .as_deref()
+            .or_else(|| self.font.as_ref().and_then(Font::family));
+
+        if let Some(font_family) = font_family {
+            text.assign("font-family", escape(font_family));
+        }
+
+        if let Some(color) = &style.text_color {
+            text.assign("fill", escape(color));
+        }
+
+        text
+    }
+
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
         svg::save(path, &self.document).context(format!("Failed to export to {:?}", path))
     }
 
+    /// Exports a self-contained html page with the svg, which supports pan, zoom, highlight & search.
+    /// Needs [`with_data_attributes`](Self::with_data_attributes) for highlight & search.
+    pub fn export_html<P: AsRef<Path>>(&self, path: P, title: &str) -> Result<()> {
+        let path = path.as_ref();
+        let html = html::create_html(title, &self.export_as_string()?);
+
+        fs::write(path, html).context(format!("Failed to export to {:?}", path))
+    }
+
+    /// Rasterises the svg into a png. See [`raster::rasterize`].
+    pub fn export_png<P: AsRef<Path>>(&self, path: P, options: &PngOptions) -> Result<()> {
+        let path = path.as_ref();
+        let data = self.export_as_png(options)?;
+
+        fs::write(path, data).context(format!("Failed to export to {:?}", path))
+    }
+
+    /// Uses the font of the builder or DejaVu Sans for the text.
+    pub fn export_as_png(&self, options: &PngOptions) -> Result<Vec<u8>> {
+        let font = self.get_text_font()?;
+
+        raster::rasterize(&self.export_as_string()?, &font, options)
+    }
+
+    /// Prints the svg on one or more pages of a pdf. See [`pdf::convert`].
+    pub fn export_pdf<P: AsRef<Path>>(&self, path: P, options: &PdfOptions) -> Result<()> {
+        let path = path.as_ref();
+        let data = self.export_as_pdf(options)?;
+
+        fs::write(path, data).context(format!("Failed to export to {:?}", path))
+    }
+
+    /// Uses the font of the builder or DejaVu Sans for the text.
+    pub fn export_as_pdf(&self, options: &PdfOptions) -> Result<Vec<u8>> {
+        let font = self.get_text_font()?;
+
+        pdf::convert(&self.export_as_string()?, &font, options)
+    }
+
+    /// Returns the font of the builder or the bundled font, which is missing without the feature `bundled-font`.
+    fn get_text_font(&self) -> Result<Cow<'_, Font>> {
+        match &self.font {
+            Some(font) => Ok(Cow::Borrowed(font)),
+            #[cfg(feature = "bundled-font")]
+            None => Ok(Cow::Owned(Font::bundled())),
+            #[cfg(not(feature = "bundled-font"))]
+            None => anyhow::bail!("A font is required without the feature `bundled-font`"),
+        }
+    }
+
     pub fn export_as_string(&self) -> Result<String> {
         let mut buf = BufWriter::new(Vec::new());
 
@@ -64,12 +331,35 @@ impl SvgBuilder {
     }
 }
 
+/// Escapes the special characters of text & attributes, which the svg crate doesn't do.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn create_text(text: &str) -> svg::node::Text {
+    svg::node::Text::new(escape(text))
+}
+
 impl Renderer for SvgBuilder {
     fn init(&mut self, width: u32, height: u32) {
         self.document.assign("viewBox", (0, 0, width, height));
+
+        if let Some(background) = &self.theme.background {
+            let background = Rectangle::new()
+                .set("x", 0)
+                .set("y", 0)
+                .set("width", width)
+                .set("height", height)
+                .set("fill", escape(background));
+
+            self.document.append(background);
+        }
     }
 
-    fn render_link(&mut self, points: Vec<(u32, u32)>) {
+    fn render_link(&mut self, points: Vec<(u32, u32)>, is_dimmed: bool) {
         if let Some((start, line)) = points.split_first() {
             let mut arrow_data = Data::new().move_to(*start);
 
@@ -77,60 +367,247 @@ impl Renderer for SvgBuilder {
                 arrow_data = arrow_data.line_to(*point);
             }
 
-            let arrow_path = Path::new()
-                .set("marker-end", "url(#head)")
+            let mut arrow_path = PathNode::new();
+
+            if self.theme.link.arrowhead != ArrowHead::None {
+                arrow_path.assign("marker-end", "url(#head)");
+            }
+
+            let mut arrow_path = arrow_path
                 .set("fill", "none")
-                .set("stroke", "black")
-                .set("stroke-width", 1)
+                .set("stroke", escape(&self.theme.link.color))
+                .set("stroke-width", self.theme.link.width)
                 .set("d", arrow_data);
 
+            if is_dimmed {
+                arrow_path.assign("opacity", DIMMED_OPACITY);
+            }
+
             self.document.append(arrow_path);
         }
     }
 
-    fn render_technology(&mut self, text: &str, x: u32, y: u32) {
+    fn render_junction(&mut self, x: u32, y: u32, is_any: bool) {
+        let color = self.theme.link.color.as_str();
+        // OR-junctions are hollow & AND-junctions are filled
+        let fill = if is_any {
+            let link = &self.theme.link;
+            link.junction_fill
+                .as_ref()
+                .or(self.theme.background.as_ref())
+                .map_or("white", String::as_str)
+        } else {
+            color
+        };
+
+        let junction = Circle::new()
+            .set("cx", x)
+            .set("cy", y)
+            .set("r", JUNCTION_RADIUS)
+            .set("fill", escape(fill))
+            .set("stroke", escape(color))
+            .set("stroke-width", self.theme.link.width);
+
+        self.document.append(junction);
+    }
+
+    fn render_era(&mut self, name: &str, x: u32, y: u32, width: u32, height: u32) {
+        let era_fills = &self.theme.era_fills;
+
+        if !era_fills.is_empty() {
+            // Neighbouring eras alternate their colors
+            let band = Rectangle::new()
+                .set("x", x)
+                .set("y", y)
+                .set("width", width)
+                .set("height", height)
+                .set("fill", escape(&era_fills[self.era_count % era_fills.len()]));
+
+            self.document.append(band);
+        }
+
+        self.era_count += 1;
+
+        let label = Text::new()
+            .set("x", x + self.text_padding / 2)
+            .set("y", y + self.font_size)
+            .set("font-size", self.font_size)
+            .set("font-style", "italic")
+            .add(create_text(name));
+
+        let label = self.apply_text_style(label, &self.theme.node);
+        self.document.append(label);
+    }
+
+    fn render_technology(&mut self, technology: &TechnologyBox) {
+        let TechnologyBox { y, .. } = *technology;
+        let left = technology.x.saturating_sub(technology.width / 2);
+        let icon = technology
+            .icon
+            .map(|icon| self.create_icon(icon, left + self.text_padding, y));
+        // The text is centered in the space right of the icon
+        let x = match icon {
+            Some(_) => technology.x + self.get_icon_space() / 2,
+            None => technology.x,
+        };
         let text_offset = self.font_size / 3;
-        let width = self.get_text_width(text);
-        let width_half = width / 2;
+        let style = self.theme.get_node_style(technology.category);
 
-        let box_node = Rectangle::new()
-            .set("x", x - width_half)
-            .set("y", y.saturating_sub(self.font_size))
-            .set("width", width)
-            .set("height", self.get_text_height())
-            .set("fill", "#4fc3ff")
-            .set("stroke", "black")
-            .set("stroke-width", 1);
+        let mut box_node = Rectangle::new()
+            .set("x", left)
+            .set("y", y.saturating_sub(technology.height / 2))
+            .set("width", technology.width)
+            .set("height", technology.height)
+            .set(
+                "fill",
+                escape(&self.get_fill_color(technology.status, technology.category)),
+            )
+            .set("stroke", escape(&style.stroke))
+            .set("stroke-width", style.stroke_width);
 
-        let text_element = svg::node::Text::new(text);
+        if style.corner_radius > 0 {
+            box_node.assign("rx", style.corner_radius);
+        }
 
-        let text_node = Text::new()
+        let mut text_node = Text::new()
             .set("x", x)
-            .set("y", y + text_offset)
             .set("font-size", self.font_size)
-            .set("text-anchor", "middle")
-            //.set("dominant-baseline", "middle")
-            .add(text_element);
+            .set("text-anchor", "middle");
+        let lines = self.get_lines(technology.text);
+
+        if let [line] = lines.as_slice() {
+            text_node = text_node.set("y", y + text_offset).add(create_text(line));
+        } else {
+            // The lines are centered vertically around the center of the box
+            let line_height = self.get_line_height();
+            let first_y =
+                (y + text_offset).saturating_sub((lines.len() as u32 - 1) * line_height / 2);
+
+            for (index, line) in lines.iter().enumerate() {
+                // The svg crate has no type for tspan elements
+                let mut span = Element::new("tspan");
+                span.assign("x", x);
+                span.assign("y", first_y + index as u32 * line_height);
+                span.append(create_text(line));
+                text_node = text_node.add(span);
+            }
+        }
+
+        let text_node = self.apply_text_style(text_node, &style);
 
-        self.document.append(box_node);
-        self.document.append(text_node);
+        if technology.description.is_none() && !self.has_data_attributes {
+            self.document.append(box_node);
+
+            if let Some(icon) = icon {
+                self.document.append(icon);
+            }
+
+            self.document.append(text_node);
+            return;
+        }
+
+        let mut group = Group::new();
+
+        if self.has_data_attributes {
+            let predecessors: Vec<String> = technology
+                .predecessors
+                .iter()
+                .map(|id| id.id().to_string())
+                .collect();
+
+            group = group
+                .set("class", "technology")
+                .set("data-id", technology.id.id())
+                .set("data-name", escape(technology.text))
+                .set("data-predecessors", predecessors.join(" "));
+        }
+
+        // Browsers show the title of the group as a tooltip for the box & the text
+        if let Some(description) = technology.description {
+            group = group.add(Title::new().add(create_text(description)));
+        }
+
+        group = group.add(box_node);
+
+        if let Some(icon) = icon {
+            group = group.add(icon);
+        }
+
+        self.document.append(group.add(text_node));
     }
 
-    fn get_size_of_technology(&self, text: &str) -> (u32, u32) {
-        (self.get_text_width(text), self.get_text_height())
+    fn get_size_of_technology(&self, text: &str, has_icon: bool) -> (u32, u32) {
+        let lines = self.get_lines(text);
+        let width = lines
+            .iter()
+            .map(|line| self.get_line_width(line))
+            .max()
+            .unwrap_or_default();
+        // The icon fits into the height of a single line
+        let icon_space = if has_icon { self.get_icon_space() } else { 0 };
+
+        (
+            width + 2 * self.text_padding + icon_space,
+            self.get_text_height(lines.len()),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::theme::{CategoryStyle, LinkStyle};
+    use std::collections::BTreeMap;
+    use tech_tree::model::technology::TechnologyId;
+
+    /// Renders a technology at x = 50 with its own size.
+    fn render(
+        builder: &mut SvgBuilder,
+        text: &str,
+        y: u32,
+        status: Option<ResearchStatus>,
+        category: Option<&str>,
+    ) {
+        let (width, height) = builder.get_size_of_technology(text, false);
+
+        builder.render_technology(&TechnologyBox {
+            id: TechnologyId::new(0),
+            predecessors: &[],
+            text,
+            x: 50,
+            y,
+            width,
+            height,
+            status,
+            category,
+            description: None,
+            icon: None,
+        });
+    }
 
     #[test]
     fn test_get_size_of_technology() {
         let builder = SvgBuilder::new(10, 20);
 
-        assert_eq!(builder.get_size_of_technology("test"), (60, 20));
-        assert_eq!(builder.get_size_of_technology("another"), (75, 20));
+        assert_eq!(builder.get_size_of_technology("test", false), (60, 20));
+        assert_eq!(builder.get_size_of_technology("another", false), (75, 20));
+        assert_eq!(builder.get_size_of_technology("Métal", false), (65, 20));
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_get_size_of_technology_with_font() {
+        let font = Font::bundled();
+        let builder = SvgBuilder::new(10, 20).with_font(font.clone());
+
+        assert_eq!(
+            builder.get_size_of_technology("Métallurgie", false),
+            (font.get_width("Métallurgie", 10) + 40, 20)
+        );
+        assert!(
+            builder.get_size_of_technology("WWW", false).0
+                > builder.get_size_of_technology("iii", false).0
+        );
     }
 
     #[test]
@@ -138,9 +615,9 @@ mod tests {
         let mut builder = SvgBuilder::new(10, 10);
 
         builder.init(100, 150);
-        builder.render_technology("Tech 1", 50, 20);
-        builder.render_technology("Tech 2", 50, 70);
-        builder.render_link(vec![(50, 30), (50, 60)]);
+        render(&mut builder, "Tech 1", 20, None, None);
+        render(&mut builder, "Tech 2", 70, None, None);
+        builder.render_link(vec![(50, 30), (50, 60)], false);
 
         let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
 <defs>
@@ -161,4 +638,381 @@ Tech 2
 
         assert_eq!(builder.export_as_string().unwrap(), result.to_string());
     }
+
+    #[test]
+    fn test_get_size_of_wrapped_technology() {
+        let builder = SvgBuilder::new(10, 20).with_max_text_width(80);
+
+        assert_eq!(
+            builder.get_size_of_technology("Advanced Orbital Manufacturing Techniques", false),
+            (120, 44)
+        );
+        assert_eq!(builder.get_size_of_technology("Short", false), (65, 20));
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_export_png() {
+        let mut builder = SvgBuilder::new(10, 10);
+
+        builder.init(100, 150);
+        render(&mut builder, "Tech", 20, None, None);
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("tree.png");
+        builder
+            .export_png(&path, &PngOptions::default().with_scale(2.0))
+            .unwrap();
+
+        let data = fs::read(path).unwrap();
+        let mut reader = png::Decoder::new(data.as_slice()).read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (200, 300));
+
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+
+        // The text inside the border of the box is drawn with the bundled font
+        let row = &pixels[(38 * 200 + 64) * 4..(38 * 200 + 136) * 4];
+        assert!(row.chunks(4).any(|pixel| pixel[..3] == [0, 0, 0]));
+    }
+
+    #[test]
+    fn test_export_wrapped_text() {
+        let mut builder = SvgBuilder::new(10, 10).with_max_text_width(50);
+
+        builder.init(100, 150);
+        render(&mut builder, "Orbital Manufacturing", 50, None, None);
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("<rect fill=\"#4fc3ff\" height=\"32\" stroke=\"black\" stroke-width=\"1\" width=\"85\" x=\"8\" y=\"34\"/>"));
+        assert!(result.contains(
+            "<text font-size=\"10\" text-anchor=\"middle\" x=\"50\">
+<tspan x=\"50\" y=\"47\">
+Orbital
+</tspan>
+<tspan x=\"50\" y=\"59\">
+Manufacturing
+</tspan>
+</text>"
+        ));
+    }
+
+    #[test]
+    fn test_export_with_description() {
+        let mut builder = SvgBuilder::new(10, 10);
+
+        builder.init(100, 150);
+        builder.render_technology(&TechnologyBox {
+            id: TechnologyId::new(0),
+            predecessors: &[],
+            text: "Tech 1",
+            x: 50,
+            y: 20,
+            width: 50,
+            height: 20,
+            status: None,
+            category: None,
+            description: Some("Unlocks <Swords> & Shields"),
+            icon: None,
+        });
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("<g>
+<title>
+Unlocks &lt;Swords&gt; &amp; Shields
+</title>
+<rect fill=\"#4fc3ff\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"10\"/>
+<text font-size=\"10\" text-anchor=\"middle\" x=\"50\" y=\"23\">
+Tech 1
+</text>
+</g>"));
+    }
+
+    #[test]
+    fn test_get_size_of_technology_with_icon() {
+        let builder = SvgBuilder::new(10, 20);
+
+        assert_eq!(builder.get_size_of_technology("test", true), (85, 20));
+    }
+
+    #[test]
+    fn test_export_with_icon() {
+        let mut builder = SvgBuilder::new(10, 10);
+
+        builder.init(100, 150);
+        builder.render_technology(&TechnologyBox {
+            id: TechnologyId::new(0),
+            predecessors: &[],
+            text: "Tech 1",
+            x: 50,
+            y: 20,
+            width: 70,
+            height: 20,
+            status: None,
+            category: None,
+            description: None,
+            icon: Some("icons/tech1.png"),
+        });
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("<rect fill=\"#4fc3ff\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"70\" x=\"15\" y=\"10\"/>
+<image height=\"15\" href=\"icons/tech1.png\" width=\"15\" x=\"25\" y=\"13\"/>
+<text font-size=\"10\" text-anchor=\"middle\" x=\"60\" y=\"23\">"));
+    }
+
+    #[test]
+    fn test_embed_icon() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tech1.svg");
+        fs::write(&path, "<svg/>").unwrap();
+
+        let mut builder = SvgBuilder::new(10, 10);
+        builder.embed_icon("icons/tech1.svg", &path).unwrap();
+
+        assert!(builder
+            .create_icon("icons/tech1.svg", 0, 20)
+            .to_string()
+            .contains("href=\"data:image/svg+xml;base64,PHN2Zy8+\""));
+        assert!(builder
+            .embed_icon("icons/tech1.bmp", &dir.path().join("tech1.bmp"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_link_icon() {
+        let mut builder = SvgBuilder::new(10, 10);
+        builder.link_icon("icons/tech1.png", "../icons/tech1.png");
+
+        assert!(builder
+            .create_icon("icons/tech1.png", 0, 20)
+            .to_string()
+            .contains("href=\"../icons/tech1.png\""));
+        assert!(builder
+            .create_icon("icons/\"a\" & b.png", 0, 20)
+            .to_string()
+            .contains("href=\"icons/&quot;a&quot; &amp; b.png\""));
+    }
+
+    #[test]
+    fn test_export_with_data_attributes() {
+        let mut builder = SvgBuilder::new(10, 10).with_data_attributes();
+
+        builder.init(100, 150);
+        builder.render_technology(&TechnologyBox {
+            id: TechnologyId::new(2),
+            predecessors: &[TechnologyId::new(0), TechnologyId::new(1)],
+            text: "Sword & \"Shield\"",
+            x: 50,
+            y: 20,
+            width: 50,
+            height: 20,
+            status: None,
+            category: None,
+            description: None,
+            icon: None,
+        });
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("<g class=\"technology\" data-id=\"2\" data-name=\"Sword &amp; &quot;Shield&quot;\" data-predecessors=\"0 1\">
+<rect "));
+    }
+
+    #[test]
+    fn test_render_junction() {
+        let mut builder = SvgBuilder::new(10, 10);
+
+        builder.init(100, 150);
+        builder.render_junction(10, 20, true);
+        builder.render_junction(30, 40, false);
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("<circle cx=\"10\" cy=\"20\" fill=\"white\" r=\"4\" stroke=\"black\" stroke-width=\"1\"/>"));
+        assert!(result.contains("<circle cx=\"30\" cy=\"40\" fill=\"black\" r=\"4\" stroke=\"black\" stroke-width=\"1\"/>"));
+    }
+
+    #[test]
+    fn test_render_era() {
+        let mut builder = SvgBuilder::new(10, 10);
+
+        builder.init(100, 150);
+        builder.render_era("Ancient", 0, 0, 100, 50);
+        builder.render_era("Medieval", 0, 50, 100, 100);
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(
+            result.contains("<rect fill=\"#f5f5f5\" height=\"50\" width=\"100\" x=\"0\" y=\"0\"/>")
+        );
+        assert!(result
+            .contains("<rect fill=\"#e0e0e0\" height=\"100\" width=\"100\" x=\"0\" y=\"50\"/>"));
+        assert!(result.contains(
+            "<text font-size=\"10\" font-style=\"italic\" x=\"5\" y=\"60\">\nMedieval\n</text>"
+        ));
+    }
+
+    #[test]
+    fn test_export_with_categories() {
+        let mut builder = SvgBuilder::new(10, 10);
+
+        builder.init(100, 150);
+        render(&mut builder, "Tech 1", 20, None, Some("Military"));
+        render(&mut builder, "Tech 2", 70, None, Some("Economy"));
+        render(&mut builder, "Tech 3", 120, None, Some("Military"));
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("<rect fill=\"#4fc3ff\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"10\"/>"));
+        assert!(result.contains("<rect fill=\"#ffb74d\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"60\"/>"));
+        assert!(result.contains("<rect fill=\"#4fc3ff\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"110\"/>"));
+    }
+
+    #[test]
+    fn test_export_with_node_fill() {
+        let theme = Theme {
+            node: NodeStyle {
+                fill: Some("gray".to_string()),
+                ..NodeStyle::default()
+            },
+            ..Theme::default()
+        };
+        let mut builder = SvgBuilder::new(10, 10).with_theme(theme);
+
+        builder.init(100, 100);
+        render(&mut builder, "Tech 1", 20, None, Some("Military"));
+        render(&mut builder, "Tech 2", 70, None, None);
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("<rect fill=\"gray\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"10\"/>"));
+        assert!(result.contains("<rect fill=\"gray\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"60\"/>"));
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_export_with_font_family() {
+        let serif = CategoryStyle {
+            font_family: Some("serif".to_string()),
+            ..CategoryStyle::default()
+        };
+        let theme = Theme {
+            categories: BTreeMap::from([("Military".to_string(), serif)]),
+            ..Theme::default()
+        };
+        let mut builder = SvgBuilder::new(10, 10)
+            .with_theme(theme)
+            .with_font(Font::bundled());
+
+        builder.init(100, 100);
+        render(&mut builder, "Tech 1", 20, None, Some("Military"));
+        render(&mut builder, "Tech 2", 70, None, None);
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("<text font-family=\"serif\" font-size=\"10\" text-anchor=\"middle\" x=\"50\" y=\"23\">"));
+        assert!(result.contains("<text font-family=\"DejaVu Sans\" font-size=\"10\" text-anchor=\"middle\" x=\"50\" y=\"73\">"));
+    }
+
+    #[test]
+    fn test_export_escapes_theme() {
+        let theme = Theme {
+            node: NodeStyle {
+                font_family: Some("A \"B\" & C".to_string()),
+                text_color: Some("\"red\"".to_string()),
+                ..NodeStyle::default()
+            },
+            ..Theme::default()
+        };
+        let mut builder = SvgBuilder::new(10, 10).with_theme(theme);
+
+        builder.init(100, 50);
+        render(&mut builder, "Tech 1", 20, None, None);
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("fill=\"&quot;red&quot;\" font-family=\"A &quot;B&quot; &amp; C\""));
+    }
+
+    #[test]
+    fn test_export_with_theme() {
+        let military = CategoryStyle {
+            fill: Some("red".to_string()),
+            ..CategoryStyle::default()
+        };
+        let theme = Theme {
+            background: Some("#202020".to_string()),
+            node: NodeStyle {
+                corner_radius: 3,
+                font_family: Some("serif".to_string()),
+                ..NodeStyle::default()
+            },
+            link: LinkStyle {
+                color: "white".to_string(),
+                width: 2,
+                arrowhead: ArrowHead::None,
+                junction_fill: None,
+            },
+            categories: BTreeMap::from([("Military".to_string(), military)]),
+            ..Theme::default()
+        };
+        let mut builder = SvgBuilder::new(10, 10).with_theme(theme);
+
+        builder.init(100, 150);
+        render(&mut builder, "Tech 1", 20, None, Some("Military"));
+        builder.render_link(vec![(50, 30), (50, 60)], false);
+        builder.render_junction(50, 70, true);
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+<defs/>
+<rect fill=\"#202020\" height=\"150\" width=\"100\" x=\"0\" y=\"0\"/>
+<rect fill=\"red\" height=\"20\" rx=\"3\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"10\"/>
+<text font-family=\"serif\" font-size=\"10\" text-anchor=\"middle\" x=\"50\" y=\"23\">
+Tech 1
+</text>
+<path d=\"M50,30 L50,60\" fill=\"none\" stroke=\"white\" stroke-width=\"2\"/>
+<circle cx=\"50\" cy=\"70\" fill=\"#202020\" r=\"4\" stroke=\"white\" stroke-width=\"2\"/>
+</svg>";
+
+        assert_eq!(builder.export_as_string().unwrap(), result.to_string());
+    }
+
+    #[test]
+    fn test_export_with_status() {
+        let mut builder = SvgBuilder::new(10, 10);
+
+        builder.init(100, 150);
+        render(
+            &mut builder,
+            "Tech 1",
+            20,
+            Some(ResearchStatus::Researched),
+            None,
+        );
+        render(
+            &mut builder,
+            "Tech 2",
+            70,
+            Some(ResearchStatus::Available),
+            None,
+        );
+        render(
+            &mut builder,
+            "Tech 3",
+            120,
+            Some(ResearchStatus::Locked),
+            None,
+        );
+        builder.render_link(vec![(50, 80), (50, 110)], true);
+
+        let result = builder.export_as_string().unwrap();
+
+        assert!(result.contains("<rect fill=\"#66bb6a\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"10\"/>"));
+        assert!(result.contains("<rect fill=\"#ffd54f\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"60\"/>"));
+        assert!(result.contains("<rect fill=\"#bdbdbd\" height=\"20\" stroke=\"black\" stroke-width=\"1\" width=\"50\" x=\"25\" y=\"110\"/>"));
+        assert!(result.contains("<path d=\"M50,80 L50,110\" fill=\"none\" marker-end=\"url(#head)\" opacity=\"0.3\" stroke=\"black\" stroke-width=\"1\"/>"));
+    }
 }
@@ -0,0 +1,44 @@
+use crate::font::Font;
+use anyhow::{Context, Result};
+use resvg::usvg::{fontdb, Options, PostProcessingSteps, Tree, TreeParsing, TreePostProc};
+use std::path::PathBuf;
+
+/// Parses the svg for rasterising or printing & converts its text into paths.
+/// The text is rendered with the font & optionally falls back to the fonts of the system,
+/// which are slow to load. Without them, all other font families fall back to the font.
+pub(crate) fn parse_svg(
+    svg: &str,
+    font: &Font,
+    dpi: f32,
+    resources_dir: Option<PathBuf>,
+    has_system_fonts: bool,
+) -> Result<Tree> {
+    let mut fonts = fontdb::Database::new();
+    fonts.load_font_data(font.data().to_vec());
+
+    if has_system_fonts {
+        fonts.load_system_fonts();
+    } else if let Some(family) = font.family() {
+        // Unknown families fall back to serif
+        fonts.set_serif_family(family);
+        fonts.set_sans_serif_family(family);
+        fonts.set_cursive_family(family);
+        fonts.set_fantasy_family(family);
+        fonts.set_monospace_family(family);
+    }
+
+    let mut options = Options {
+        dpi,
+        resources_dir,
+        ..Options::default()
+    };
+
+    if let Some(family) = font.family() {
+        options.font_family = family.to_string();
+    }
+
+    let mut tree = Tree::from_str(svg, &options).context("Failed to parse the svg")?;
+    tree.postprocess(PostProcessingSteps::default(), &fonts);
+
+    Ok(tree)
+}
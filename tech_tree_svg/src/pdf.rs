@@ -0,0 +1,563 @@
+use crate::font::Font;
+use crate::parsing::parse_svg;
+use crate::raster::SVG_DPI;
+use anyhow::{anyhow, bail, Error, Result};
+use pdf_writer::{Content, Name, Pdf, Rect, Ref};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const POINTS_PER_INCH: f32 = 72.0;
+const MILLIMETERS_PER_INCH: f32 = 25.4;
+/// The font size of the page labels in points.
+const LABEL_SIZE: u32 = 8;
+/// The minimum margin of tiled pages in millimeters, which fits the marks & the label.
+const MIN_TILED_MARGIN: f32 = 8.0;
+/// The maximum number of pages of a tiled tree.
+const MAX_PAGES: f32 = 1000.0;
+const TREE: Name = Name(b"Tree");
+const LABEL: Name = Name(b"Label");
+
+/// The size of a sheet of paper in millimeters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PaperSize {
+    width: f32,
+    height: f32,
+}
+
+impl PaperSize {
+    pub const A0: PaperSize = PaperSize::new(841.0, 1189.0);
+    pub const A1: PaperSize = PaperSize::new(594.0, 841.0);
+    pub const A2: PaperSize = PaperSize::new(420.0, 594.0);
+    pub const A3: PaperSize = PaperSize::new(297.0, 420.0);
+    pub const A4: PaperSize = PaperSize::new(210.0, 297.0);
+    pub const A5: PaperSize = PaperSize::new(148.0, 210.0);
+    pub const LETTER: PaperSize = PaperSize::new(215.9, 279.4);
+    pub const LEGAL: PaperSize = PaperSize::new(215.9, 355.6);
+    pub const TABLOID: PaperSize = PaperSize::new(279.4, 431.8);
+
+    pub const fn new(width: f32, height: f32) -> Self {
+        PaperSize { width, height }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// Returns the size with the longer side as width.
+    pub fn to_landscape(self) -> Self {
+        PaperSize::new(self.width.max(self.height), self.width.min(self.height))
+    }
+}
+
+impl FromStr for PaperSize {
+    type Err = Error;
+
+    /// Parses a name like `a4` or `letter` or a custom size in millimeters like `600x400`.
+    fn from_str(text: &str) -> Result<Self> {
+        let size = match text.to_lowercase().as_str() {
+            "a0" => PaperSize::A0,
+            "a1" => PaperSize::A1,
+            "a2" => PaperSize::A2,
+            "a3" => PaperSize::A3,
+            "a4" => PaperSize::A4,
+            "a5" => PaperSize::A5,
+            "letter" => PaperSize::LETTER,
+            "legal" => PaperSize::LEGAL,
+            "tabloid" => PaperSize::TABLOID,
+            custom => {
+                let (width, height) = custom
+                    .split_once('x')
+                    .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+                    .ok_or_else(|| anyhow!("Unknown paper size of {:?}", text))?;
+                PaperSize::new(width, height)
+            }
+        };
+
+        let is_valid = |length: f32| length.is_finite() && length > 0.0;
+
+        if !(is_valid(size.width) && is_valid(size.height)) {
+            bail!("Invalid paper size of {:?}", text);
+        }
+
+        Ok(size)
+    }
+}
+
+/// How the svg is printed on one or more pages.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfOptions {
+    paper: PaperSize,
+    is_landscape: bool,
+    /// The blank border of each page in millimeters, which contains the marks & labels.
+    /// Tiled pages need at least 8 mm.
+    margin: f32,
+    /// The width of the area printed on both of two neighbouring pages in millimeters.
+    overlap: f32,
+    scale: f32,
+    is_fit_to_page: bool,
+    /// Resolves linked icons with relative paths, if present.
+    resources_dir: Option<PathBuf>,
+    has_system_fonts: bool,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        PdfOptions {
+            paper: PaperSize::A4,
+            is_landscape: false,
+            margin: 10.0,
+            overlap: 10.0,
+            scale: 1.0,
+            is_fit_to_page: false,
+            resources_dir: None,
+            has_system_fonts: false,
+        }
+    }
+}
+
+impl PdfOptions {
+    pub fn with_paper(self, paper: PaperSize) -> Self {
+        Self { paper, ..self }
+    }
+
+    /// Turns the paper, so that its longer side is horizontal.
+    pub fn with_landscape(self) -> Self {
+        Self {
+            is_landscape: true,
+            ..self
+        }
+    }
+
+    pub fn with_margin(self, margin: f32) -> Self {
+        Self { margin, ..self }
+    }
+
+    pub fn with_overlap(self, overlap: f32) -> Self {
+        Self { overlap, ..self }
+    }
+
+    /// Multiplies the size of the tree, which is printed with 96 dpi by default.
+    pub fn with_scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Shrinks or enlarges the tree to fill a single page. Overrides the scale.
+    pub fn with_fit_to_page(self) -> Self {
+        Self {
+            is_fit_to_page: true,
+            ..self
+        }
+    }
+
+    /// The directory of the linked icons with relative paths.
+    pub fn with_resources_dir(self, resources_dir: PathBuf) -> Self {
+        Self {
+            resources_dir: Some(resources_dir),
+            ..self
+        }
+    }
+
+    /// Falls back to the fonts of the system for characters missing in the font.
+    pub fn with_system_fonts(self) -> Self {
+        Self {
+            has_system_fonts: true,
+            ..self
+        }
+    }
+
+    /// Returns the paper in the chosen orientation.
+    pub fn get_paper(&self) -> PaperSize {
+        if self.is_landscape {
+            self.paper.to_landscape()
+        } else {
+            self.paper
+        }
+    }
+}
+
+/// The positions of the tree on the pages in points. The origin of pdf pages is bottom left.
+struct Tiling {
+    page_size: (f32, f32),
+    margin: f32,
+    overlap: f32,
+    tree_size: (f32, f32),
+    /// The offsets of the tree for each column.
+    columns: Vec<f32>,
+    /// The offsets of the tree for each row.
+    rows: Vec<f32>,
+}
+
+impl Tiling {
+    fn new(svg_size: (f32, f32), options: &PdfOptions) -> Result<Self> {
+        let paper = options.get_paper();
+        let page_size = (to_points(paper.width), to_points(paper.height));
+        let margin = to_points(options.margin);
+        let overlap = to_points(options.overlap);
+        let printable = (page_size.0 - 2.0 * margin, page_size.1 - 2.0 * margin);
+
+        if !(margin >= 0.0 && overlap >= 0.0) {
+            bail!(
+                "Invalid margin of {} mm or overlap of {} mm",
+                options.margin,
+                options.overlap
+            );
+        } else if printable.0.min(printable.1) <= overlap {
+            bail!(
+                "The margin of {} mm & the overlap of {} mm leave no space on the paper",
+                options.margin,
+                options.overlap
+            );
+        }
+
+        let scale = if options.is_fit_to_page {
+            (printable.0 / svg_size.0).min(printable.1 / svg_size.1)
+        } else if options.scale > 0.0 && options.scale.is_finite() {
+            options.scale * POINTS_PER_INCH / SVG_DPI
+        } else {
+            bail!("Invalid scale of {}", options.scale);
+        };
+        let tree_size = (svg_size.0 * scale, svg_size.1 * scale);
+        let page_count = count_tiles(tree_size.0, printable.0, overlap)
+            * count_tiles(tree_size.1, printable.1, overlap);
+
+        if page_count.is_nan() || page_count > MAX_PAGES {
+            bail!(
+                "The tree needs {} pages, but at most {} are supported. Reduce the scale or use a larger paper",
+                page_count,
+                MAX_PAGES
+            );
+        }
+
+        let tiling = Tiling {
+            page_size,
+            margin,
+            overlap,
+            tree_size,
+            columns: calculate_tiles(tree_size.0, printable.0, overlap),
+            rows: calculate_tiles(tree_size.1, printable.1, overlap),
+        };
+
+        if tiling.is_tiled() && options.margin < MIN_TILED_MARGIN {
+            bail!(
+                "The margin of {} mm leaves no space for the marks & labels of multiple pages, which need {} mm",
+                options.margin,
+                MIN_TILED_MARGIN
+            );
+        }
+
+        Ok(tiling)
+    }
+
+    /// Is the tree split into multiple pages?
+    fn is_tiled(&self) -> bool {
+        self.columns.len() * self.rows.len() > 1
+    }
+
+    /// Creates a svg with the position of a page, whose text is drawn with the font of the tree.
+    fn create_label(&self, row: usize, column: usize, font: &Font) -> String {
+        let label = format!(
+            "Row {} of {}, column {} of {}",
+            row + 1,
+            self.rows.len(),
+            column + 1,
+            self.columns.len()
+        );
+        let width = font.get_width(&label, LABEL_SIZE);
+        let height = LABEL_SIZE * 5 / 4;
+
+        format!(
+            "<svg viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\">
+<text font-size=\"{}\" x=\"0\" y=\"{}\">{}</text>
+</svg>",
+            width, height, LABEL_SIZE, LABEL_SIZE, label
+        )
+    }
+
+    /// Draws the part of the tree on a page. The label of a tiled page has the given size in points.
+    fn create_page(&self, row: usize, column: usize, label_size: Option<(f32, f32)>) -> Vec<u8> {
+        let (width, height) = self.page_size;
+        let (left, top) = (self.margin, height - self.margin);
+        let (right, bottom) = (width - self.margin, self.margin);
+        let mut content = Content::new();
+
+        content.save_state();
+        content
+            .rect(left, bottom, right - left, top - bottom)
+            .clip_nonzero()
+            .end_path();
+        // The form of the tree is a unit square
+        content.transform([
+            self.tree_size.0,
+            0.0,
+            0.0,
+            self.tree_size.1,
+            left - self.columns[column],
+            top + self.rows[row] - self.tree_size.1,
+        ]);
+        content.x_object(TREE);
+        content.restore_state();
+
+        if !self.is_tiled() {
+            return content.finish();
+        }
+
+        // Ticks in the margins at the borders of the overlapping areas
+        let tick = self.margin / 2.0;
+        content.set_stroke_gray(0.0).set_line_width(0.5);
+
+        if column > 0 {
+            mark_column(&mut content, left + self.overlap, top, bottom, tick);
+        }
+
+        if column + 1 < self.columns.len() {
+            mark_column(&mut content, right - self.overlap, top, bottom, tick);
+        }
+
+        if row > 0 {
+            mark_row(&mut content, top - self.overlap, left, right, tick);
+        }
+
+        if row + 1 < self.rows.len() {
+            mark_row(&mut content, bottom + self.overlap, left, right, tick);
+        }
+
+        content.stroke();
+
+        // The label is centered in the outer half of the bottom margin below the ticks
+        if let Some((width, height)) = label_size {
+            content.save_state();
+            content.transform([width, 0.0, 0.0, height, left, (tick - height) / 2.0]);
+            content.x_object(LABEL);
+            content.restore_state();
+        }
+
+        content.finish()
+    }
+}
+
+fn to_points(millimeters: f32) -> f32 {
+    millimeters * POINTS_PER_INCH / MILLIMETERS_PER_INCH
+}
+
+/// Calculates the offset of the tree on each page along one axis.
+/// Neighbouring pages share the overlap & a tree fitting on a single page is centered.
+fn calculate_tiles(size: f32, printable: f32, overlap: f32) -> Vec<f32> {
+    if size <= printable {
+        return vec![(size - printable) / 2.0];
+    }
+
+    let step = printable - overlap;
+    let count = count_tiles(size, printable, overlap) as usize;
+
+    (0..count).map(|index| index as f32 * step).collect()
+}
+
+/// Counts the pages along one axis, which is infinite or NaN for an invalid size.
+fn count_tiles(size: f32, printable: f32, overlap: f32) -> f32 {
+    if size <= printable {
+        1.0
+    } else {
+        ((size - overlap) / (printable - overlap)).ceil()
+    }
+}
+
+fn mark_column(content: &mut Content, x: f32, top: f32, bottom: f32, tick: f32) {
+    content.move_to(x, top).line_to(x, top + tick);
+    content.move_to(x, bottom).line_to(x, bottom - tick);
+}
+
+fn mark_row(content: &mut Content, y: f32, left: f32, right: f32, tick: f32) {
+    content.move_to(left, y).line_to(left - tick, y);
+    content.move_to(right, y).line_to(right + tick, y);
+}
+
+/// Converts a svg into a pdf with vector graphics.
+/// A tree larger than the paper is split into pages, which are ordered row by row.
+/// Each of them is labelled with its position & has marks at the borders of the overlap.
+/// The labels are drawn as paths with the font, so the pdf needs no fonts.
+pub fn convert(svg: &str, font: &Font, options: &PdfOptions) -> Result<Vec<u8>> {
+    let tree = parse_svg(
+        svg,
+        font,
+        SVG_DPI,
+        options.resources_dir.clone(),
+        options.has_system_fonts,
+    )?;
+    let tiling = Tiling::new((tree.size.width(), tree.size.height()), options)?;
+
+    let mut pdf = Pdf::new();
+    let catalog_ref = Ref::new(1);
+    let page_tree_ref = Ref::new(2);
+    let tree_ref = Ref::new(3);
+    let mut next_ref = svg2pdf::convert_tree_into(&tree, Default::default(), &mut pdf, tree_ref);
+    let mut page_refs = Vec::new();
+
+    for row in 0..tiling.rows.len() {
+        for column in 0..tiling.columns.len() {
+            let page_ref = next_ref;
+            let content_ref = page_ref.next();
+            next_ref = content_ref.next();
+
+            let label = if tiling.is_tiled() {
+                let label_tree = parse_svg(
+                    &tiling.create_label(row, column, font),
+                    font,
+                    SVG_DPI,
+                    None,
+                    options.has_system_fonts,
+                )?;
+                let label_ref = next_ref;
+                next_ref = svg2pdf::convert_tree_into(
+                    &label_tree,
+                    Default::default(),
+                    &mut pdf,
+                    label_ref,
+                );
+                Some((
+                    label_ref,
+                    (label_tree.size.width(), label_tree.size.height()),
+                ))
+            } else {
+                None
+            };
+
+            {
+                let mut page = pdf.page(page_ref);
+                page.media_box(Rect::new(0.0, 0.0, tiling.page_size.0, tiling.page_size.1))
+                    .parent(page_tree_ref)
+                    .contents(content_ref);
+                let mut resources = page.resources();
+                let mut x_objects = resources.x_objects();
+                x_objects.pair(TREE, tree_ref);
+
+                if let Some((label_ref, _)) = label {
+                    x_objects.pair(LABEL, label_ref);
+                }
+            }
+
+            let label_size = label.map(|(_, size)| size);
+            pdf.stream(content_ref, &tiling.create_page(row, column, label_size));
+            page_refs.push(page_ref);
+        }
+    }
+
+    pdf.catalog(catalog_ref).pages(page_tree_ref);
+    pdf.pages(page_tree_ref)
+        .count(page_refs.len() as i32)
+        .kids(page_refs);
+    Ok(pdf.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "bundled-font")]
+    const SVG: &str = "<svg viewBox=\"0 0 400 200\" xmlns=\"http://www.w3.org/2000/svg\">
+<rect fill=\"#ff0000\" height=\"200\" width=\"400\" x=\"0\" y=\"0\"/>
+</svg>";
+
+    #[cfg(feature = "bundled-font")]
+    fn count_pages(pdf: &[u8]) -> usize {
+        let pdf = String::from_utf8_lossy(pdf);
+
+        pdf.matches("/Type /Page").count() - pdf.matches("/Type /Pages").count()
+    }
+
+    #[test]
+    fn test_paper_size_from_str() {
+        assert_eq!("A4".parse::<PaperSize>().unwrap(), PaperSize::A4);
+        assert_eq!("letter".parse::<PaperSize>().unwrap(), PaperSize::LETTER);
+        assert_eq!(
+            "600x400".parse::<PaperSize>().unwrap(),
+            PaperSize::new(600.0, 400.0)
+        );
+        assert!("b4".parse::<PaperSize>().is_err());
+        assert!("0x400".parse::<PaperSize>().is_err());
+        assert!("infx400".parse::<PaperSize>().is_err());
+        assert!("600xNaN".parse::<PaperSize>().is_err());
+    }
+
+    #[test]
+    fn test_to_landscape() {
+        assert_eq!(PaperSize::A4.to_landscape(), PaperSize::new(297.0, 210.0));
+        assert_eq!(
+            PaperSize::new(300.0, 200.0).to_landscape(),
+            PaperSize::new(300.0, 200.0)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_create_label() {
+        let options = PdfOptions::default().with_paper(PaperSize::new(120.0, 120.0));
+        let tiling = Tiling::new((800.0, 400.0), &options).unwrap();
+
+        assert!(tiling
+            .create_label(0, 1, &Font::bundled())
+            .contains(">Row 1 of 2, column 2 of 3</text>"));
+    }
+
+    #[test]
+    fn test_calculate_tiles() {
+        assert_eq!(calculate_tiles(100.0, 200.0, 10.0), vec![-50.0]);
+        assert_eq!(calculate_tiles(200.0, 200.0, 10.0), vec![0.0]);
+        assert_eq!(calculate_tiles(500.0, 200.0, 20.0), vec![0.0, 180.0, 360.0]);
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_convert_single_page() {
+        let pdf = convert(SVG, &Font::bundled(), &PdfOptions::default()).unwrap();
+
+        assert!(pdf.starts_with(b"%PDF"));
+        assert_eq!(count_pages(&pdf), 1);
+        assert!(!String::from_utf8_lossy(&pdf).contains("/Label"));
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_convert_tiles() {
+        // The printable area of 100 x 100 mm contains about 378 x 378 units
+        let options = PdfOptions::default()
+            .with_paper(PaperSize::new(120.0, 120.0))
+            .with_scale(2.0);
+        let pdf = convert(SVG, &Font::bundled(), &options).unwrap();
+
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert_eq!(count_pages(&pdf), 6);
+        assert_eq!(text.matches("/Label").count(), 12);
+        assert!(!text.contains("/Font"));
+
+        let pdf = convert(SVG, &Font::bundled(), &options.with_fit_to_page()).unwrap();
+        assert_eq!(count_pages(&pdf), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_invalid_options() {
+        let options = PdfOptions::default();
+
+        assert!(convert(SVG, &Font::bundled(), &options.clone().with_margin(105.0)).is_err());
+        assert!(convert(SVG, &Font::bundled(), &options.clone().with_scale(0.0)).is_err());
+        assert!(convert(
+            SVG,
+            &Font::bundled(),
+            &options.clone().with_scale(f32::INFINITY)
+        )
+        .is_err());
+        assert!(convert(SVG, &Font::bundled(), &options.clone().with_scale(100000.0)).is_err());
+
+        let tiled = options
+            .with_paper(PaperSize::A5)
+            .with_scale(2.0)
+            .with_margin(5.0);
+        assert!(convert(SVG, &Font::bundled(), &tiled).is_err());
+        assert!(convert(SVG, &Font::bundled(), &tiled.with_fit_to_page()).is_ok());
+    }
+}
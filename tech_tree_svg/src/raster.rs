@@ -0,0 +1,207 @@
+use crate::font::Font;
+use crate::parsing::parse_svg;
+use anyhow::{anyhow, bail, Context, Result};
+use png::{BitDepth, ColorType, Encoder, PixelDimensions, Unit};
+use resvg::tiny_skia::{Pixmap, Transform};
+use std::path::PathBuf;
+
+/// The resolution of the svg, where one unit is one pixel.
+pub const SVG_DPI: f32 = 96.0;
+
+const INCHES_PER_METER: f32 = 39.3701;
+
+/// How the svg is rasterised into a png.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PngOptions {
+    scale: f32,
+    dpi: f32,
+    /// Resolves linked icons with relative paths, if present.
+    resources_dir: Option<PathBuf>,
+    has_system_fonts: bool,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        PngOptions {
+            scale: 1.0,
+            dpi: SVG_DPI,
+            resources_dir: None,
+            has_system_fonts: false,
+        }
+    }
+}
+
+impl PngOptions {
+    /// Multiplies the size of the image.
+    pub fn with_scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Stores the resolution in the png & enlarges the image to keep its size in inches.
+    pub fn with_dpi(self, dpi: f32) -> Self {
+        Self { dpi, ..self }
+    }
+
+    /// The directory of the linked icons with relative paths.
+    pub fn with_resources_dir(self, resources_dir: PathBuf) -> Self {
+        Self {
+            resources_dir: Some(resources_dir),
+            ..self
+        }
+    }
+
+    /// Falls back to the fonts of the system for characters missing in the font.
+    pub fn with_system_fonts(self) -> Self {
+        Self {
+            has_system_fonts: true,
+            ..self
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn dpi(&self) -> f32 {
+        self.dpi
+    }
+
+    /// Returns the number of pixels per unit of the svg.
+    pub fn get_zoom(&self) -> f32 {
+        self.scale * self.dpi / SVG_DPI
+    }
+}
+
+/// Rasterises a svg on the CPU & encodes it as png.
+pub fn rasterize(svg: &str, font: &Font, options: &PngOptions) -> Result<Vec<u8>> {
+    if !(options.scale > 0.0 && options.dpi > 0.0) {
+        bail!(
+            "Invalid scale of {} or dpi of {}",
+            options.scale,
+            options.dpi
+        );
+    }
+
+    let tree = parse_svg(
+        svg,
+        font,
+        options.dpi,
+        options.resources_dir.clone(),
+        options.has_system_fonts,
+    )?;
+
+    let zoom = options.get_zoom();
+    let size = tree
+        .size
+        .to_int_size()
+        .scale_by(zoom)
+        .ok_or_else(|| anyhow!("Invalid zoom of {}", zoom))?;
+    let mut pixmap = Pixmap::new(size.width(), size.height()).ok_or_else(|| {
+        anyhow!(
+            "Failed to allocate an image of {}x{} pixels",
+            size.width(),
+            size.height()
+        )
+    })?;
+
+    resvg::render(
+        &tree,
+        Transform::from_scale(zoom, zoom),
+        &mut pixmap.as_mut(),
+    );
+
+    encode(&pixmap, options.dpi)
+}
+
+/// Encodes the pixels with straight alpha & the resolution in pixels per meter.
+fn encode(pixmap: &Pixmap, dpi: f32) -> Result<Vec<u8>> {
+    let pixels: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    let pixels_per_meter = (dpi * INCHES_PER_METER).round() as u32;
+    let mut data = Vec::new();
+
+    {
+        let mut encoder = Encoder::new(&mut data, pixmap.width(), pixmap.height());
+        encoder.set_color(ColorType::Rgba);
+        encoder.set_depth(BitDepth::Eight);
+        encoder.set_pixel_dims(Some(PixelDimensions {
+            xppu: pixels_per_meter,
+            yppu: pixels_per_meter,
+            unit: Unit::Meter,
+        }));
+
+        let mut writer = encoder
+            .write_header()
+            .context("Failed to write the png header")?;
+        writer
+            .write_image_data(&pixels)
+            .context("Failed to write the png data")?;
+        writer.finish().context("Failed to finish the png")?;
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "bundled-font")]
+    use png::Decoder;
+
+    #[cfg(feature = "bundled-font")]
+    const SVG: &str = "<svg viewBox=\"0 0 40 20\" xmlns=\"http://www.w3.org/2000/svg\">
+<rect fill=\"#ff0000\" height=\"20\" width=\"40\" x=\"0\" y=\"0\"/>
+</svg>";
+
+    #[test]
+    fn test_get_zoom() {
+        assert_eq!(PngOptions::default().get_zoom(), 1.0);
+        assert_eq!(PngOptions::default().with_scale(2.0).get_zoom(), 2.0);
+        assert_eq!(PngOptions::default().with_dpi(144.0).get_zoom(), 1.5);
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_rasterize() {
+        let options = PngOptions::default().with_scale(0.5).with_dpi(192.0);
+        let data = rasterize(SVG, &Font::bundled(), &options).unwrap();
+
+        let mut reader = Decoder::new(data.as_slice()).read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (40, 20));
+        assert_eq!(info.pixel_dims.unwrap().xppu, 7559);
+
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(pixels[..4], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_rasterize_unknown_font_family() {
+        let svg = "<svg viewBox=\"0 0 40 20\" xmlns=\"http://www.w3.org/2000/svg\">
+<text font-family=\"Unknown\" font-size=\"20\" x=\"0\" y=\"18\">XX</text>
+</svg>";
+        let data = rasterize(svg, &Font::bundled(), &PngOptions::default()).unwrap();
+
+        let mut reader = Decoder::new(data.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        // Drawn with the bundled font instead of being skipped
+        assert!(pixels.chunks(4).any(|pixel| pixel[3] > 0));
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-font")]
+    fn test_invalid_options() {
+        let options = PngOptions::default().with_scale(0.0);
+
+        assert!(rasterize(SVG, &Font::bundled(), &options).is_err());
+    }
+}
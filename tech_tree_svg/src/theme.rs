@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Fills the boxes, if neither the theme nor a category does.
+pub const DEFAULT_FILL: &str = "#4fc3ff";
+
+/// The style of the rendered tree, which can be loaded from yaml.
+/// Missing fields use the default style.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// The color behind the whole tree. Transparent, if missing.
+    pub background: Option<String>,
+    pub node: NodeStyle,
+    pub link: LinkStyle,
+    pub researched_fill: String,
+    /// Differs from [`DEFAULT_FILL`] by default, so available technologies stand out from a tree without research state.
+    pub available_fill: String,
+    pub locked_fill: String,
+    /// Fills the boxes of categories in the order of their first appearance,
+    /// if neither [`NodeStyle::fill`] nor the override of the category is set.
+    pub category_fills: Vec<String>,
+    /// Fills the bands of the eras alternately.
+    pub era_fills: Vec<String>,
+    /// Overrides the node style for the technologies of a category.
+    pub categories: BTreeMap<String, CategoryStyle>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            background: None,
+            node: NodeStyle::default(),
+            link: LinkStyle::default(),
+            researched_fill: "#66bb6a".to_string(),
+            available_fill: "#ffd54f".to_string(),
+            locked_fill: "#bdbdbd".to_string(),
+            category_fills: [
+                "#4fc3ff", "#ffb74d", "#ba68c8", "#e57373", "#81c784", "#fff176",
+            ]
+            .iter()
+            .map(|fill| fill.to_string())
+            .collect(),
+            era_fills: vec!["#f5f5f5".to_string(), "#e0e0e0".to_string()],
+            categories: BTreeMap::new(),
+        }
+    }
+}
+
+impl Theme {
+    /// Returns the node style of a category with its overrides.
+    pub fn get_node_style(&self, category: Option<&str>) -> NodeStyle {
+        match category.and_then(|category| self.categories.get(category)) {
+            Some(style) => style.apply(&self.node),
+            None => self.node.clone(),
+        }
+    }
+
+    /// Returns the font families of the node style & the overrides of the categories.
+    pub fn get_font_families(&self) -> Vec<&str> {
+        self.node
+            .font_family
+            .iter()
+            .chain(
+                self.categories
+                    .values()
+                    .flat_map(|style| &style.font_family),
+            )
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// The style of the box & text of a technology.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NodeStyle {
+    /// Uses [`Theme::category_fills`] or [`DEFAULT_FILL`], if missing.
+    pub fill: Option<String>,
+    pub stroke: String,
+    pub stroke_width: u32,
+    pub corner_radius: u32,
+    /// Uses the family of the measuring font of the [`SvgBuilder`](crate::SvgBuilder)
+    /// or the default font of the viewer, if missing.
+    /// The boxes only fit the text, if the measuring font has this family.
+    pub font_family: Option<String>,
+    pub text_color: Option<String>,
+}
+
+impl Default for NodeStyle {
+    fn default() -> Self {
+        NodeStyle {
+            fill: None,
+            stroke: "black".to_string(),
+            stroke_width: 1,
+            corner_radius: 0,
+            font_family: None,
+            text_color: None,
+        }
+    }
+}
+
+/// Overrides parts of a [`NodeStyle`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CategoryStyle {
+    pub fill: Option<String>,
+    pub stroke: Option<String>,
+    pub stroke_width: Option<u32>,
+    pub corner_radius: Option<u32>,
+    pub font_family: Option<String>,
+    pub text_color: Option<String>,
+}
+
+impl CategoryStyle {
+    fn apply(&self, style: &NodeStyle) -> NodeStyle {
+        NodeStyle {
+            fill: self.fill.clone().or_else(|| style.fill.clone()),
+            stroke: self.stroke.clone().unwrap_or_else(|| style.stroke.clone()),
+            stroke_width: self.stroke_width.unwrap_or(style.stroke_width),
+            corner_radius: self.corner_radius.unwrap_or(style.corner_radius),
+            font_family: self
+                .font_family
+                .clone()
+                .or_else(|| style.font_family.clone()),
+            text_color: self.text_color.clone().or_else(|| style.text_color.clone()),
+        }
+    }
+}
+
+/// The style of the links & junctions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkStyle {
+    pub color: String,
+    pub width: u32,
+    pub arrowhead: ArrowHead,
+    /// Fills the hollow OR-junctions. Uses [`Theme::background`] or white, if missing.
+    pub junction_fill: Option<String>,
+}
+
+impl Default for LinkStyle {
+    fn default() -> Self {
+        LinkStyle {
+            color: "black".to_string(),
+            width: 1,
+            arrowhead: ArrowHead::Triangle,
+            junction_fill: None,
+        }
+    }
+}
+
+/// The shape at the end of each link.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArrowHead {
+    #[default]
+    Triangle,
+    /// A triangle without fill & its closing side.
+    Open,
+    Circle,
+    None,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let theme: Theme = serde_yaml::from_str(
+            "---
+background: \"#202020\"
+node:
+  corner_radius: 5
+  font_family: serif
+link:
+  color: white
+  arrowhead: open
+categories:
+  Military:
+    fill: red
+",
+        )
+        .unwrap();
+
+        assert_eq!(theme.background, Some("#202020".to_string()));
+        assert_eq!(theme.node.fill, None);
+        assert_eq!(theme.node.corner_radius, 5);
+        assert_eq!(theme.link.width, 1);
+        assert_eq!(theme.link.arrowhead, ArrowHead::Open);
+        assert_eq!(theme.available_fill, "#ffd54f");
+        assert_eq!(theme.locked_fill, "#bdbdbd");
+
+        let military = theme.get_node_style(Some("Military"));
+        assert_eq!(military.fill, Some("red".to_string()));
+        assert_eq!(military.font_family, Some("serif".to_string()));
+        assert_eq!(theme.get_node_style(Some("Economy")), theme.node);
+        assert_eq!(theme.get_font_families(), vec!["serif"]);
+    }
+}
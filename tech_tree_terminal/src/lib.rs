@@ -0,0 +1,652 @@
+use tech_tree::model::technology::research::ResearchStatus;
+use tech_tree::rendering::renderer::{Renderer, TechnologyBox};
+use tech_tree::rendering::text::wrap_text;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// The padding of the [`TreeRenderer`](tech_tree::rendering::tree::TreeRenderer) in characters,
+/// which leaves enough rows & columns for the links & junctions between the boxes.
+pub const PADDING: u32 = 3;
+/// Links end this many characters before a box, which leaves room for the arrowheads of other backends.
+const ARROW_GAP: usize = 3;
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+/// The characters used for drawing.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Charset {
+    /// Box-drawing characters.
+    #[default]
+    Unicode,
+    /// Only ASCII characters for terminals without unicode support.
+    Ascii,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Cell {
+    Empty,
+    /// The band of an era, which is overwritten by everything else.
+    Background(char),
+    /// The directions of all links passing this cell & whether all of them are dimmed.
+    Line(u8, bool),
+    /// The border, text or interior of a box, which links can't pass.
+    Box(char),
+    /// A junction or arrowhead, which links can't pass.
+    Symbol(char),
+    /// The second column of a wide character in a box, which prints nothing.
+    Wide,
+}
+
+/// Renders a tech tree into a grid of characters, where each unit of the layout is 1 column.
+/// Wide characters like CJK take 2 columns & zero-width characters are skipped.
+///
+/// The border of each box shows its research status:
+/// light lines are locked, heavy lines are available & double lines are researched.
+/// Links into locked technologies are dashed.
+/// Diagonal links are replaced by horizontal & vertical segments,
+/// but [`EdgeRouting::Orthogonal`](tech_tree::rendering::routing::EdgeRouting::Orthogonal) looks better.
+pub struct TerminalRenderer {
+    charset: Charset,
+    /// Wraps longer text into multiple lines, if present.
+    max_text_width: Option<u32>,
+    width: usize,
+    cells: Vec<Cell>,
+}
+
+impl TerminalRenderer {
+    pub fn new(charset: Charset) -> Self {
+        Self {
+            charset,
+            max_text_width: None,
+            width: 0,
+            cells: Vec::new(),
+        }
+    }
+
+    /// Wraps text wider than the maximum number of characters into multiple lines.
+    pub fn with_max_text_width(self, max_text_width: u32) -> Self {
+        Self {
+            max_text_width: Some(max_text_width),
+            ..self
+        }
+    }
+
+    /// Returns the rendered lines without trailing whitespace & without the empty rows above & below.
+    pub fn export_as_string(&self) -> String {
+        let mut lines: Vec<String> = Vec::new();
+
+        if self.width > 0 {
+            for row in self.cells.chunks(self.width) {
+                let line: String = row
+                    .iter()
+                    .filter(|cell| **cell != Cell::Wide)
+                    .map(|cell| self.get_char(*cell))
+                    .collect();
+                lines.push(line.trim_end().to_string());
+            }
+        }
+
+        let start = lines.iter().position(|line| !line.is_empty());
+        let end = lines.iter().rposition(|line| !line.is_empty());
+
+        match (start, end) {
+            (Some(start), Some(end)) => lines[start..=end]
+                .iter()
+                .map(|line| format!("{}\n", line))
+                .collect(),
+            _ => String::new(),
+        }
+    }
+
+    fn get_char(&self, cell: Cell) -> char {
+        match cell {
+            Cell::Empty | Cell::Wide => ' ',
+            Cell::Background(c) | Cell::Box(c) | Cell::Symbol(c) => c,
+            Cell::Line(directions, is_dimmed) => self.get_line_char(directions, is_dimmed),
+        }
+    }
+
+    /// Dimmed lines are dashed, but their corners & crossings are solid.
+    fn get_line_char(&self, directions: u8, is_dimmed: bool) -> char {
+        let is_vertical = directions & (LEFT | RIGHT) == 0;
+        let is_horizontal = directions & (UP | DOWN) == 0;
+
+        match self.charset {
+            Charset::Ascii if is_vertical && is_dimmed => ':',
+            Charset::Ascii if is_vertical => '|',
+            Charset::Ascii if is_horizontal && is_dimmed => '.',
+            Charset::Ascii if is_horizontal => '-',
+            Charset::Ascii => '+',
+            Charset::Unicode if is_vertical && is_dimmed => '╎',
+            Charset::Unicode if is_vertical => '│',
+            Charset::Unicode if is_horizontal && is_dimmed => '╌',
+            Charset::Unicode if is_horizontal => '─',
+            Charset::Unicode => match directions {
+                d if d == DOWN | RIGHT => '┌',
+                d if d == DOWN | LEFT => '┐',
+                d if d == UP | RIGHT => '└',
+                d if d == UP | LEFT => '┘',
+                d if d == UP | DOWN | RIGHT => '├',
+                d if d == UP | DOWN | LEFT => '┤',
+                d if d == DOWN | LEFT | RIGHT => '┬',
+                d if d == UP | LEFT | RIGHT => '┴',
+                _ => '┼',
+            },
+        }
+    }
+
+    /// The corners, the horizontal & the vertical border of a box.
+    fn get_box_chars(&self, status: Option<ResearchStatus>) -> [char; 6] {
+        match (self.charset, status) {
+            (Charset::Unicode, Some(ResearchStatus::Researched)) => ['╔', '╗', '╚', '╝', '═', '║'],
+            (Charset::Unicode, Some(ResearchStatus::Available)) => ['┏', '┓', '┗', '┛', '━', '┃'],
+            (Charset::Unicode, _) => ['┌', '┐', '└', '┘', '─', '│'],
+            (Charset::Ascii, Some(ResearchStatus::Researched)) => ['#', '#', '#', '#', '=', '#'],
+            (Charset::Ascii, Some(ResearchStatus::Available)) => ['+', '+', '+', '+', '=', '|'],
+            (Charset::Ascii, _) => ['+', '+', '+', '+', '-', '|'],
+        }
+    }
+
+    /// Dimmed arrowheads are hollow, except for ASCII.
+    fn get_arrow(&self, direction: u8, is_dimmed: bool) -> char {
+        match (self.charset, direction, is_dimmed) {
+            (Charset::Unicode, UP, true) => '△',
+            (Charset::Unicode, LEFT, true) => '◁',
+            (Charset::Unicode, RIGHT, true) => '▷',
+            (Charset::Unicode, _, true) => '▽',
+            (Charset::Unicode, UP, false) => '▲',
+            (Charset::Unicode, LEFT, false) => '◀',
+            (Charset::Unicode, RIGHT, false) => '▶',
+            (Charset::Unicode, _, false) => '▼',
+            (Charset::Ascii, UP, _) => '^',
+            (Charset::Ascii, LEFT, _) => '<',
+            (Charset::Ascii, RIGHT, _) => '>',
+            (Charset::Ascii, _, _) => 'v',
+        }
+    }
+
+    fn get_lines(&self, text: &str) -> Vec<String> {
+        match self.max_text_width {
+            Some(max_width) => wrap_text(text, max_width, |line| line.width() as u32),
+            None => vec![text.to_string()],
+        }
+    }
+
+    fn get_index(&self, (x, y): (usize, usize)) -> Option<usize> {
+        if x < self.width && y * self.width < self.cells.len() {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    fn get(&self, point: (usize, usize)) -> Option<Cell> {
+        self.get_index(point).map(|index| self.cells[index])
+    }
+
+    fn set(&mut self, point: (usize, usize), cell: Cell) {
+        if let Some(index) = self.get_index(point) {
+            self.cells[index] = cell;
+        }
+    }
+
+    /// Only empty cells, backgrounds & other links can be overwritten by links.
+    fn is_free(&self, point: (usize, usize)) -> bool {
+        matches!(
+            self.get(point),
+            Some(Cell::Empty | Cell::Background(_) | Cell::Line(..))
+        )
+    }
+
+    fn is_arrow(&self, point: (usize, usize)) -> bool {
+        match self.get(point) {
+            Some(Cell::Symbol(c)) => [UP, DOWN, LEFT, RIGHT].iter().any(|direction| {
+                self.get_arrow(*direction, false) == c || self.get_arrow(*direction, true) == c
+            }),
+            _ => false,
+        }
+    }
+
+    /// A line stays dimmed, until a link without dimming passes it.
+    fn add_direction(&mut self, point: (usize, usize), direction: u8, is_dimmed: bool) {
+        if let Some(index) = self.get_index(point) {
+            self.cells[index] = match self.cells[index] {
+                Cell::Line(directions, was_dimmed) => {
+                    Cell::Line(directions | direction, was_dimmed && is_dimmed)
+                }
+                Cell::Empty | Cell::Background(_) => Cell::Line(direction, is_dimmed),
+                cell => cell,
+            }
+        }
+    }
+
+    /// Writes text into a box, which ends before the column `max_x`.
+    fn write_text(&mut self, text: &str, x: usize, y: usize, max_x: usize) {
+        let mut column = x;
+
+        for c in text.chars() {
+            let width = c.width().unwrap_or_default();
+
+            if width == 0 {
+                continue;
+            } else if column + width > max_x {
+                break;
+            }
+
+            self.set((column, y), Cell::Box(c));
+
+            for wide_column in column + 1..column + width {
+                self.set((wide_column, y), Cell::Wide);
+            }
+
+            column += width;
+        }
+    }
+
+    /// Extends a link ending shortly before a box, so that its arrowhead touches the box.
+    /// The direction of its first segment, which leaves the source box towards the next row,
+    /// is preferred, followed by the direction of its last segment.
+    fn extend_to_box(&self, path: &mut Vec<(usize, usize)>) {
+        let (first, second, previous, last) = match path.as_slice() {
+            [first, second, .., previous, last] => (*first, *second, *previous, *last),
+            _ => return,
+        };
+
+        if matches!(self.get(last), Some(Cell::Box(_))) {
+            return;
+        }
+
+        let mut directions = vec![get_direction(first, second), get_direction(previous, last)];
+        directions.extend([DOWN, RIGHT, UP, LEFT]);
+        directions.dedup();
+
+        for direction in directions {
+            let mut extension = Vec::new();
+            let mut point = last;
+
+            for _ in 0..ARROW_GAP {
+                match step(point, direction) {
+                    Some(next) if matches!(self.get(next), Some(Cell::Box(_))) => {
+                        extension.push(next);
+                        path.extend(extension);
+                        remove_detours(path);
+                        return;
+                    }
+                    // Passes the arrowheads of other links to the same box
+                    Some(next) if self.is_free(next) || self.is_arrow(next) => {
+                        extension.push(next);
+                        point = next;
+                    }
+                    _ => break,
+                }
+            }
+        }
+    }
+}
+
+/// Returns the neighbour of a cell in a direction.
+fn step((x, y): (usize, usize), direction: u8) -> Option<(usize, usize)> {
+    match direction {
+        UP => Some((x, y.checked_sub(1)?)),
+        DOWN => Some((x, y + 1)),
+        LEFT => Some((x.checked_sub(1)?, y)),
+        _ => Some((x + 1, y)),
+    }
+}
+
+/// Removes the parts of a path, which go back the way they came.
+fn remove_detours(path: &mut Vec<(usize, usize)>) {
+    let mut result: Vec<(usize, usize)> = Vec::with_capacity(path.len());
+
+    for point in path.iter() {
+        if result.len() >= 2 && result[result.len() - 2] == *point {
+            result.pop();
+        } else {
+            result.push(*point);
+        }
+    }
+
+    *path = result;
+}
+
+/// Returns the direction from a cell to its neighbour.
+fn get_direction(from: (usize, usize), to: (usize, usize)) -> u8 {
+    if to.1 < from.1 {
+        UP
+    } else if to.1 > from.1 {
+        DOWN
+    } else if to.0 < from.0 {
+        LEFT
+    } else {
+        RIGHT
+    }
+}
+
+fn get_opposite(direction: u8) -> u8 {
+    match direction {
+        UP => DOWN,
+        DOWN => UP,
+        LEFT => RIGHT,
+        _ => LEFT,
+    }
+}
+
+/// Returns all cells of a link, which replaces each diagonal segment
+/// with 3 segments split at the middle of its longer axis.
+fn trace(points: &[(u32, u32)]) -> Vec<(usize, usize)> {
+    let mut corners = Vec::new();
+
+    for segment in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+        corners.push((x0, y0));
+
+        if x0 != x1 && y0 != y1 {
+            if y0.abs_diff(y1) >= x0.abs_diff(x1) {
+                let y = (y0 + y1) / 2;
+                corners.push((x0, y));
+                corners.push((x1, y));
+            } else {
+                let x = (x0 + x1) / 2;
+                corners.push((x, y0));
+                corners.push((x, y1));
+            }
+        }
+    }
+
+    corners.extend(points.last());
+
+    let mut path: Vec<(usize, usize)> = Vec::new();
+
+    for segment in corners.windows(2) {
+        let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+        let length = x0.abs_diff(x1).max(y0.abs_diff(y1));
+
+        for i in 0..=length {
+            let x = if x1 >= x0 {
+                x0 + i.min(x1 - x0)
+            } else {
+                x0 - i.min(x0 - x1)
+            };
+            let y = if y1 >= y0 {
+                y0 + i.min(y1 - y0)
+            } else {
+                y0 - i.min(y0 - y1)
+            };
+            let point = (x as usize, y as usize);
+
+            if path.last() != Some(&point) {
+                path.push(point);
+            }
+        }
+    }
+
+    if path.is_empty() {
+        path.extend(corners.first().map(|(x, y)| (*x as usize, *y as usize)));
+    }
+
+    path
+}
+
+impl Renderer for TerminalRenderer {
+    fn init(&mut self, width: u32, height: u32) {
+        // The layout's coordinates include the right & bottom edge
+        self.width = width as usize + 1;
+        self.cells = vec![Cell::Empty; self.width * (height as usize + 1)];
+    }
+
+    fn render_link(&mut self, points: Vec<(u32, u32)>, is_dimmed: bool) {
+        let mut path = trace(&points);
+        self.extend_to_box(&mut path);
+
+        for pair in path.windows(2) {
+            let direction = get_direction(pair[0], pair[1]);
+
+            if self.is_free(pair[0]) {
+                self.add_direction(pair[0], direction, is_dimmed);
+            }
+
+            if self.is_free(pair[1]) {
+                self.add_direction(pair[1], get_opposite(direction), is_dimmed);
+            }
+        }
+
+        // The arrowhead is placed in front of the box, unless another link already placed one
+        let is_box = |point: &(usize, usize)| matches!(self.get(*point), Some(Cell::Box(_)));
+        let box_start = path.len() - path.iter().rev().take_while(|p| is_box(p)).count();
+
+        if box_start > 0 && box_start < path.len() && self.is_free(path[box_start - 1]) {
+            let direction = get_direction(path[box_start - 1], path[box_start]);
+            let arrow = self.get_arrow(direction, is_dimmed);
+            self.set(path[box_start - 1], Cell::Symbol(arrow));
+        }
+    }
+
+    fn render_junction(&mut self, x: u32, y: u32, is_any: bool) {
+        let symbol = match (self.charset, is_any) {
+            (Charset::Unicode, true) => '○',
+            (Charset::Unicode, false) => '●',
+            (Charset::Ascii, true) => 'o',
+            (Charset::Ascii, false) => '*',
+        };
+
+        self.set((x as usize, y as usize), Cell::Symbol(symbol));
+    }
+
+    fn render_era(&mut self, name: &str, x: u32, y: u32, width: u32, height: u32) {
+        let (x, y) = (x as usize, y as usize);
+        let (width, height) = (width as usize, height as usize);
+
+        // A dotted line along the top or left edge separates the eras
+        if width >= height {
+            let c = match self.charset {
+                Charset::Unicode => '┄',
+                Charset::Ascii => '.',
+            };
+
+            for x in x..x + width {
+                self.set((x, y), Cell::Background(c));
+            }
+        } else {
+            let c = match self.charset {
+                Charset::Unicode => '┆',
+                Charset::Ascii => ':',
+            };
+
+            for y in y..y + height {
+                self.set((x, y), Cell::Background(c));
+            }
+        }
+
+        let label = format!(" {} ", name);
+        self.write_text(&label, x + 1, y, self.width);
+    }
+
+    fn render_technology(&mut self, technology: &TechnologyBox) {
+        let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] =
+            self.get_box_chars(technology.status);
+        let left = technology.x.saturating_sub(technology.width / 2) as usize;
+        let top = technology.y.saturating_sub(technology.height / 2) as usize;
+        let right = left + technology.width as usize;
+        let bottom = top + technology.height as usize;
+
+        for y in top..=bottom {
+            for x in left..=right {
+                let c = match (x, y) {
+                    (x, y) if x == left && y == top => top_left,
+                    (x, y) if x == right && y == top => top_right,
+                    (x, y) if x == left && y == bottom => bottom_left,
+                    (x, y) if x == right && y == bottom => bottom_right,
+                    (_, y) if y == top || y == bottom => horizontal,
+                    (x, _) if x == left || x == right => vertical,
+                    _ => ' ',
+                };
+
+                self.set((x, y), Cell::Box(c));
+            }
+        }
+
+        let lines = self.get_lines(technology.text);
+        let first_y = (technology.y as usize).saturating_sub((lines.len() - 1) / 2);
+
+        for (index, line) in lines.iter().enumerate() {
+            let length = line.width();
+            let x = (technology.x as usize)
+                .saturating_sub(length / 2)
+                .max(left + 1);
+            self.write_text(line, x, first_y + index, right);
+        }
+    }
+
+    fn get_size_of_technology(&self, text: &str, _has_icon: bool) -> (u32, u32) {
+        let lines = self.get_lines(text);
+        let length = lines
+            .iter()
+            .map(|line| line.width())
+            .max()
+            .unwrap_or_default() as u32;
+        let line_count = lines.len() as u32;
+
+        // The size excludes the right & bottom edge, but must be even to be centered
+        ((length + 4) / 2 * 2, (line_count + 2) / 2 * 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tech_tree::model::technology::research::ResearchState;
+    use tech_tree::model::technology::{Input, TechnologyId};
+    use tech_tree::rendering::routing::EdgeRouting;
+    use tech_tree::rendering::tree::TreeRenderer;
+    use tech_tree::usecase::creation::create_tree;
+
+    fn render(charset: Charset) -> String {
+        let tree = create_tree(vec![
+            Input::test("Mining", vec![]),
+            Input::test("Pottery", vec![]),
+            Input::test("Masonry", vec!["Mining", "Pottery"]),
+        ])
+        .unwrap();
+        let mut state = ResearchState::new(&tree);
+        state.research(&tree, TechnologyId::new(0)).unwrap();
+        let mut renderer = TerminalRenderer::new(charset);
+        let mut tree_renderer = TreeRenderer::new(PADDING).with_routing(EdgeRouting::Orthogonal);
+
+        tree_renderer
+            .render(&mut renderer, &tree, Some(&state))
+            .unwrap();
+
+        renderer.export_as_string()
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(Charset::Unicode),
+            "   ╔═════════╗     ┏━━━━━━━━━┓
+   ║ Mining  ║     ┃ Pottery ┃
+   ╚═════════╝     ┗━━━━━━━━━┛
+        ╎               ╎
+        ╎               ╎
+        ╎  ┌╌╌╌╌╌╌╌╌╌╌╌╌┘
+        ╎  ╎
+        ▽  ▽
+   ┌─────────┐
+   │ Masonry │
+   └─────────┘
+"
+        );
+    }
+
+    #[test]
+    fn test_render_ascii() {
+        assert_eq!(
+            render(Charset::Ascii),
+            "   #=========#     +=========+
+   # Mining  #     | Pottery |
+   #=========#     +=========+
+        :               :
+        :               :
+        :  +............+
+        :  :
+        v  v
+   +---------+
+   | Masonry |
+   +---------+
+"
+        );
+    }
+
+    #[test]
+    fn test_get_size_of_technology() {
+        let renderer = TerminalRenderer::new(Charset::Unicode).with_max_text_width(8);
+
+        assert_eq!(renderer.get_size_of_technology("Mining", false), (10, 2));
+        assert_eq!(renderer.get_size_of_technology("Pottery", true), (10, 2));
+        assert_eq!(
+            renderer.get_size_of_technology("Bronze Age Tools", false),
+            (10, 4)
+        );
+    }
+
+    #[test]
+    fn test_render_wide_characters() {
+        let tree = create_tree(vec![Input::test("採掘", vec![])]).unwrap();
+        let mut renderer = TerminalRenderer::new(Charset::Unicode);
+
+        TreeRenderer::new(PADDING)
+            .render(&mut renderer, &tree, None)
+            .unwrap();
+
+        assert_eq!(renderer.get_size_of_technology("採掘", false), (8, 2));
+        assert_eq!(
+            renderer.export_as_string(),
+            "   ┌───────┐\n   │ 採掘  │\n   └───────┘\n"
+        );
+    }
+
+    #[test]
+    fn test_crossing_links() {
+        let mut renderer = TerminalRenderer::new(Charset::Unicode);
+
+        renderer.init(4, 4);
+        renderer.render_link(vec![(0, 2), (4, 2)], false);
+        renderer.render_link(vec![(2, 0), (2, 4)], false);
+        renderer.render_link(vec![(0, 0), (4, 4)], false);
+
+        assert_eq!(
+            renderer.export_as_string(),
+            "│ │\n│ │\n└─┼─┐\n  │ │\n  │ │\n"
+        );
+    }
+
+    #[test]
+    fn test_dimmed_links() {
+        for (charset, expected) in [
+            (Charset::Unicode, "╎ │\n╎ │\n└╌┼╌┐\n  │ ╎\n  │ ╎\n"),
+            (Charset::Ascii, ": |\n: |\n+.+.+\n  | :\n  | :\n"),
+        ] {
+            let mut renderer = TerminalRenderer::new(charset);
+
+            renderer.init(4, 4);
+            renderer.render_link(vec![(2, 0), (2, 4)], false);
+            renderer.render_link(vec![(0, 0), (4, 4)], true);
+
+            assert_eq!(renderer.export_as_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        assert_eq!(
+            trace(&[(0, 0), (2, 4)]),
+            vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 3), (2, 4)]
+        );
+        assert_eq!(
+            trace(&[(4, 0), (0, 1)]),
+            vec![(4, 0), (3, 0), (2, 0), (2, 1), (1, 1), (0, 1)]
+        );
+    }
+}